@@ -0,0 +1,29 @@
+//! `cargo bench --features rayon --bench keygen`
+
+use capy_kem::{
+    constants::parameter_sets::KEM_768,
+    fips203::keygen::{ml_kem_keygen, mlkem_keygen_batch},
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, SeedableRng};
+
+const COUNT: usize = 64;
+
+fn keygen_batch(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(0x1161);
+    let mut group = c.benchmark_group("64 x ML-KEM-768 keygen");
+    group.bench_function("sequential", |bench| {
+        bench.iter(|| {
+            (0..COUNT)
+                .map(|_| ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("mlkem_keygen_batch", |bench| {
+        bench.iter(|| mlkem_keygen_batch::<KEM_768, _>(COUNT, &mut rng).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, keygen_batch);
+criterion_main!(benches);
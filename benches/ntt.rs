@@ -0,0 +1,39 @@
+//! `cargo bench --features testing --bench ntt`
+
+use capy_kem::math::bench::Ntt;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn hadamard_product(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(0x1099);
+    let a = Ntt::from_rng(&mut rng);
+    let b = Ntt::from_rng(&mut rng);
+    c.bench_function("hadamard_product", |bench| {
+        bench.iter(|| black_box(a).hadamard_product(black_box(b)))
+    });
+}
+
+fn add_scaled(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(0x1164);
+    let acc = Ntt::from_rng(&mut rng);
+    let x = Ntt::from_rng(&mut rng);
+    let mut group = c.benchmark_group("acc += s * x");
+    group.bench_function("add_scaled", |bench| {
+        bench.iter(|| {
+            let mut acc = black_box(acc);
+            acc.add_scaled(black_box(1729), &x);
+            acc
+        })
+    });
+    group.bench_function("scale_then_add", |bench| {
+        bench.iter(|| {
+            let mut acc = black_box(acc);
+            acc.scale_then_add(black_box(1729), &x);
+            acc
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, hadamard_product, add_scaled);
+criterion_main!(benches);
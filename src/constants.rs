@@ -3,7 +3,11 @@ pub mod ml_kem_constants {
     pub const n: usize = 256;
 
     pub const ENCODE_10: usize = n * 10 / 8;
-    pub const ENCODE_12: usize = n * 12 / 8;
+    /// Bytes in `ByteEncode_12` of one polynomial, written out as a literal
+    /// for array lengths and const-generic bounds.
+    pub const ENCODE_12_CONST: usize = 384;
+    const _: () = assert!(ENCODE_12_CONST == n * 12 / 8);
+    pub const ENCODE_12: usize = ENCODE_12_CONST;
     pub const E_PKE_KEYSIZE: usize = k * ENCODE_12 + 32;
     pub const D_PKE_KEYSIZE: usize = k * ENCODE_12;
     // 32(d_u * k + d_v) - 32(d_u * k)
@@ -31,6 +35,76 @@ pub mod parameter_sets {
         type Du: EncodingSize;
         type Dv: EncodingSize;
         type Encode12: EncodingSize;
+
+        /// Human-readable name of the parameter set, e.g. `"ML-KEM-768"`,
+        /// for domain separation and logging.
+        const LABEL: &'static str;
+
+        /// NIST security category: 1 (AES-128), 3 (AES-192) or 5 (AES-256).
+        const NIST_SECURITY_CATEGORY: u8;
+
+        /// Fails to compile when evaluated for a parameter set above
+        /// category 1 with the `blake3-xof` feature enabled: BLAKE3 offers
+        /// 128-bit security, less than ML-KEM-768 and ML-KEM-1024 require.
+        ///
+        #[cfg_attr(feature = "blake3-xof", doc = "```compile_fail")]
+        #[cfg_attr(not(feature = "blake3-xof"), doc = "```")]
+        /// use capy_kem::{
+        ///     constants::parameter_sets::KEM_768, fips203::keygen::keygen_from_prf_output,
+        /// };
+        ///
+        /// let _ = keygen_from_prf_output::<KEM_768>(&[0u8; 64]);
+        /// ```
+        #[doc(hidden)]
+        const XOF_SECURITY_CHECK: () = assert!(
+            !cfg!(feature = "blake3-xof") || Self::NIST_SECURITY_CATEGORY == 1,
+            "the blake3-xof feature only supports ML-KEM-512"
+        );
+
+        /// True iff this parameter set is at least as strong as category
+        /// `min_cat`.
+        fn meets_security_category(min_cat: u8) -> bool {
+            Self::NIST_SECURITY_CATEGORY >= min_cat
+        }
+
+        /// `Du::USIZE` as a plain constant, for size arithmetic.
+        const DU_USIZE: usize;
+        /// `Dv::USIZE` as a plain constant, for size arithmetic.
+        const DV_USIZE: usize;
+
+        /// Bytes in one `ByteEncode_12` polynomial of `t_hat` or `s_hat`.
+        const BYTES_PER_T_POLY: usize = super::ml_kem_constants::ENCODE_12_CONST;
+        /// Bytes in one compressed polynomial of `u` (`32 * du`).
+        const BYTES_PER_U_POLY: usize = 32 * Self::DU_USIZE;
+        /// Bytes in the compressed polynomial `v` (`32 * dv`).
+        const BYTES_PER_V_POLY: usize = 32 * Self::DV_USIZE;
+
+        /// Bytes in an encapsulation key, `384 * k + 32`.
+        const PUBLIC_KEY_SIZE: usize = Self::BYTES_PER_T_POLY * Self::K::USIZE + 32;
+        /// Bytes in a decapsulation key, `768 * k + 96`.
+        const PRIVATE_KEY_SIZE: usize = 2 * Self::BYTES_PER_T_POLY * Self::K::USIZE + 96;
+        /// Bytes in a ciphertext, `32 * (du * k + dv)`.
+        const CIPHERTEXT_SIZE: usize =
+            Self::BYTES_PER_U_POLY * Self::K::USIZE + Self::BYTES_PER_V_POLY;
+    }
+
+    /// The largest `CIPHERTEXT_SIZE` of any parameter set (ML-KEM-1024), for
+    /// stack buffers that must fit every `P`.
+    pub(crate) const MAX_CIPHERTEXT_SIZE: usize = 1568;
+
+    /// Size in bytes of a ciphertext for `P`.
+    pub const fn ciphertext_size<P: ParameterSet>() -> usize {
+        P::CIPHERTEXT_SIZE
+    }
+
+    /// Size in bytes of an encapsulation key for `P`.
+    pub const fn public_key_size<P: ParameterSet>() -> usize {
+        P::PUBLIC_KEY_SIZE
+    }
+
+    /// Size in bytes of a decapsulation key for `P`.
+    pub const fn private_key_size<P: ParameterSet>() -> usize {
+        P::PRIVATE_KEY_SIZE
     }
 
     #[derive(Clone, Copy, PartialEq, Debug)]
@@ -43,6 +117,10 @@ pub mod parameter_sets {
         type Du = U10;
         type Dv = U4;
         type Encode12 = U12;
+        const LABEL: &'static str = "ML-KEM-512";
+        const NIST_SECURITY_CATEGORY: u8 = 1;
+        const DU_USIZE: usize = 10;
+        const DV_USIZE: usize = 4;
     }
     #[derive(Clone, Copy, PartialEq, Debug)]
     pub struct KEM_768;
@@ -54,6 +132,10 @@ pub mod parameter_sets {
         type Du = U10;
         type Dv = U4;
         type Encode12 = U12;
+        const LABEL: &'static str = "ML-KEM-768";
+        const NIST_SECURITY_CATEGORY: u8 = 3;
+        const DU_USIZE: usize = 10;
+        const DV_USIZE: usize = 4;
     }
     #[derive(Clone, Copy, PartialEq, Debug)]
     pub struct KEM_1024;
@@ -65,6 +147,42 @@ pub mod parameter_sets {
         type Du = U11;
         type Dv = U5;
         type Encode12 = U12;
+        const LABEL: &'static str = "ML-KEM-1024";
+        const NIST_SECURITY_CATEGORY: u8 = 5;
+        const DU_USIZE: usize = 11;
+        const DV_USIZE: usize = 5;
+    }
+
+    // the plain constants must agree with the typenum parameters
+    macro_rules! assert_d_consistent {
+        ($($p:ty),*) => {$(
+            const _: () = assert!(<$p>::DU_USIZE == <$p as ParameterSet>::Du::USIZE);
+            const _: () = assert!(<$p>::DV_USIZE == <$p as ParameterSet>::Dv::USIZE);
+        )*};
+    }
+    assert_d_consistent!(KEM_512, KEM_768, KEM_1024);
+
+    // FIPS 203 Table 2 (k, eta_1, eta_2, d_u, d_v) and the sizes of Table 3.
+    // Evaluated at compile time, so `cargo check` fails if any set drifts.
+    macro_rules! const_assert_param_set_consistency {
+        ($($p:ty => ($k:expr, $eta1:expr, $eta2:expr, $du:expr, $dv:expr, $ek:expr, $dk:expr, $ct:expr)),* $(,)?) => {$(
+            const _: () = assert!(<$p as ParameterSet>::K::USIZE == $k);
+            const _: () = assert!(<$p as ParameterSet>::KSquared::USIZE == $k * $k);
+            const _: () = assert!(<$p as ParameterSet>::EtaOne::USIZE == $eta1);
+            const _: () = assert!(<$p as ParameterSet>::EtaTwo::USIZE == $eta2);
+            const _: () = assert!(<$p as ParameterSet>::Du::USIZE == $du);
+            const _: () = assert!(<$p as ParameterSet>::Dv::USIZE == $dv);
+            const _: () = assert!(<$p as ParameterSet>::Encode12::USIZE == 12);
+            const _: () = assert!(<$p>::PUBLIC_KEY_SIZE == $ek);
+            const _: () = assert!(<$p>::PRIVATE_KEY_SIZE == $dk);
+            const _: () = assert!(<$p>::CIPHERTEXT_SIZE == $ct);
+            const _: () = assert!(<$p>::CIPHERTEXT_SIZE <= MAX_CIPHERTEXT_SIZE);
+        )*};
+    }
+    const_assert_param_set_consistency! {
+        KEM_512 => (2, 3, 2, 10, 4, 800, 1632, 768),
+        KEM_768 => (3, 2, 2, 10, 4, 1184, 2400, 1088),
+        KEM_1024 => (4, 2, 2, 11, 5, 1568, 3168, 1568),
     }
 }
 
@@ -1,10 +1,11 @@
 use core::fmt;
 
 /// Generic error type for ML-KEM operations
-/// 
+///
 /// Error messages are intentionally generic to avoid leaking information
 /// that could be used in timing or other side-channel attacks.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum KemError {
     /// Invalid input provided to a function
     InvalidInput,
@@ -12,6 +13,13 @@ pub enum KemError {
     DecapsulationFailure,
     /// Encoding/decoding error
     EncodingError,
+    /// A 12-bit encoded coefficient was not reduced mod q. `position` is the
+    /// byte offset of the 3-byte group that holds it.
+    BadCoefficient { position: usize, value: u16 },
+    /// The entropy source could not supply random bytes
+    EntropyFailure,
+    /// The RNG returned an all-zero buffer, which indicates a failure
+    InsufficientEntropy,
 }
 
 impl fmt::Display for KemError {
@@ -20,6 +28,11 @@ impl fmt::Display for KemError {
             KemError::InvalidInput => write!(f, "Invalid input"),
             KemError::DecapsulationFailure => write!(f, "Decapsulation failed"),
             KemError::EncodingError => write!(f, "Encoding error"),
+            KemError::BadCoefficient { position, value } => {
+                write!(f, "Coefficient {value} out of range at byte {position}")
+            }
+            KemError::EntropyFailure => write!(f, "Entropy source failure"),
+            KemError::InsufficientEntropy => write!(f, "Insufficient entropy"),
         }
     }
 }
@@ -28,4 +41,3 @@ impl fmt::Display for KemError {
 
 /// Type alias for Results using KemError
 pub type Result<T> = core::result::Result<T, KemError>;
-
@@ -1,19 +1,20 @@
-use super::encrypt::k_pke_encrypt;
+use super::{
+    encrypt::k_pke_encrypt_into, keygen::ek_hash_matches, message::Message, primitives::j,
+    shared_secret::SharedSecret,
+};
 use crate::{
-    constants::{ml_kem_constants::ENCODE_12, parameter_sets::ParameterSet},
-    error::Result,
+    constants::parameter_sets::{ParameterSet, MAX_CIPHERTEXT_SIZE},
+    error::{KemError, Result},
     math::{
-        encoding::{Compress, Encode, EncodingSize},
+        encoding::{Compress, Encode},
         ntt_element::NttElement,
-        ring_element::RingElement,
+        ring_element::{ring_element_to_message, RingElement},
+        util::ct_bytes_equal,
     },
 };
-use alloc::vec::Vec;
 use hybrid_array::{typenum::Unsigned, Array};
 use sha3::{Digest, Sha3_512};
-use subtle::ConstantTimeEq;
-use typenum::U1;
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
 
 /// FIPS 203 Section 6.3, Algorithm 17
 /// Uses the decapsulation key to produce a shared key from a ciphertext.
@@ -22,91 +23,155 @@ use zeroize::Zeroize;
 ///
 /// This function uses constant-time comparison to prevent timing attacks
 /// and zeroizes sensitive intermediate values.
-pub fn mlkem_decaps<P: ParameterSet>(c: &[u8], dk: &[u8]) -> Result<Vec<u8>> {
+///
+/// # Errors
+///
+/// Returns [`KemError::InvalidInput`] if `c` or `dk` has the wrong length
+/// for `P`, or if `dk` does not decode.
+#[must_use = "the shared secret is the only output"]
+pub fn mlkem_decaps<P: ParameterSet>(c: &[u8], dk: &[u8]) -> Result<SharedSecret> {
+    let (k, _) = decaps_inner::<P>(c, dk)?;
+    Ok(SharedSecret::from(*k))
+}
+
+/// Same as [`mlkem_decaps`], writing the shared secret into `out`.
+///
+/// `out` is only written on success. Decapsulation does not touch the heap:
+/// the polynomials, the derived keys and the re-encrypted ciphertext all
+/// live on the stack, which suits targets without an allocator.
+///
+/// # Errors
+///
+/// Same as [`mlkem_decaps`].
+pub fn mlkem_decaps_into<P: ParameterSet>(c: &[u8], dk: &[u8], out: &mut [u8; 32]) -> Result<()> {
+    let (k, _) = decaps_inner::<P>(c, dk)?;
+    out.copy_from_slice(k.as_ref());
+    Ok(())
+}
+
+/// Decapsulation that also reports whether the ciphertext check passed.
+///
+/// The shared secret is always the same value [`mlkem_decaps`] would return
+/// (`K'` on success, `K̄` on implicit rejection); the `bool` is `true` iff
+/// the re-encrypted ciphertext matched. Exposing this bit defeats the
+/// purpose of implicit rejection, so it is only available with the
+/// `testing` feature.
+#[cfg(feature = "testing")]
+pub fn mlkem_decaps_explicit<P: ParameterSet>(c: &[u8], dk: &[u8]) -> Result<(SharedSecret, bool)> {
+    let (k, valid) = decaps_inner::<P>(c, dk)?;
+    Ok((SharedSecret::from(*k), valid))
+}
+
+// Shared body of the decapsulation entry points. Returns the selected key
+// and whether the ciphertext matched its re-encryption.
+fn decaps_inner<P: ParameterSet>(c: &[u8], dk: &[u8]) -> Result<(Zeroizing<[u8; 32]>, bool)> {
+    // Everything below slices `c` and `dk` at fixed offsets
+    if c.len() != P::CIPHERTEXT_SIZE || dk.len() != P::PRIVATE_KEY_SIZE {
+        return Err(KemError::InvalidInput);
+    }
+    // Debug builds also recompute H(ek) to catch a corrupted key; release
+    // builds skip the extra hash.
+    if cfg!(debug_assertions) && !ek_hash_matches::<P>(dk) {
+        return Err(KemError::InvalidInput);
+    }
+
     // Unpack the key based on parameter k
-    let (dk_pke, ek_pke, h, z) = unpack_dk::<P>(dk);
+    let UnpackedDk {
+        dk_pke,
+        ek_pke,
+        h,
+        z,
+    } = unpack_dk::<P>(dk);
+
+    // Secret intermediates are wrapped in `Zeroizing` so they are cleared
+    // on every exit path, including early returns.
 
     // Decrypt ciphertext
-    let mut m_prime = k_pke_decrypt::<P>(dk_pke, c)?;
+    let m_prime = k_pke_decrypt::<P>(dk_pke, c)?;
 
     // Derive K' and r' from m_prime and h
-    let (mut k_prime, mut r_prime) = derive_keys(&m_prime, h);
+    let (mut k_prime, r_prime) = derive_keys(m_prime.as_ref(), h);
 
     // Compute K̄ from z and c
     let k_bar = compute_k_bar(z, c);
 
     // Re-encrypt using derived randomness r' and check ciphertext match
-    let c_prime = k_pke_encrypt::<P>(ek_pke, &m_prime, &r_prime)?;
-    
+    let mut c_prime = Zeroizing::new([0u8; MAX_CIPHERTEXT_SIZE]);
+    let c_prime = &mut c_prime[..P::CIPHERTEXT_SIZE];
+    k_pke_encrypt_into::<P>(ek_pke, m_prime.as_ref(), r_prime.as_ref(), c_prime)?;
+
     // Constant-time comparison to prevent timing attacks
-    let comparison = c.ct_eq(&c_prime);
-    if comparison.unwrap_u8() != 1 {
+    let valid = ct_bytes_equal(c, c_prime);
+    if !valid {
         k_prime = k_bar; // If ciphertexts do not match, "implicitly reject"
     }
 
-    // Zeroize sensitive intermediate values
-    m_prime.zeroize();
-    r_prime.zeroize();
+    Ok((k_prime, valid))
+}
 
-    Ok(k_prime)
+/// The four components of a decapsulation key, `dk_pke || ek_pke || h || z`.
+pub(crate) struct UnpackedDk<'a> {
+    pub(crate) dk_pke: &'a [u8],
+    pub(crate) ek_pke: &'a [u8],
+    pub(crate) h: &'a [u8],
+    pub(crate) z: &'a [u8],
 }
 
 // Extracts keys from dk based on the size multiplier k
-fn unpack_dk<P: ParameterSet>(dk: &[u8]) -> (&[u8], &[u8], &[u8], &[u8]) {
+pub(crate) fn unpack_dk<P: ParameterSet>(dk: &[u8]) -> UnpackedDk<'_> {
     let k = P::K::to_usize();
-    let dk_pke_size = ENCODE_12 * k;
-    let ek_pke_size = ENCODE_12 * k + 32;
-    let dk_pke = &dk[0..dk_pke_size];
-    let ek_pke = &dk[dk_pke_size..dk_pke_size + ek_pke_size];
-    let h = &dk[dk_pke_size + ek_pke_size..dk_pke_size + ek_pke_size + 32];
-    let z = &dk[dk_pke_size + ek_pke_size + 32..dk_pke_size + ek_pke_size + 64];
-    (dk_pke, ek_pke, h, z)
+    let dk_pke_size = P::BYTES_PER_T_POLY * k;
+    let ek_pke_size = P::BYTES_PER_T_POLY * k + 32;
+    UnpackedDk {
+        dk_pke: &dk[0..dk_pke_size],
+        ek_pke: &dk[dk_pke_size..dk_pke_size + ek_pke_size],
+        h: &dk[dk_pke_size + ek_pke_size..dk_pke_size + ek_pke_size + 32],
+        z: &dk[dk_pke_size + ek_pke_size + 32..dk_pke_size + ek_pke_size + 64],
+    }
 }
 
 // Derive K' and r' using SHA3-512 hasher
-fn derive_keys(m_prime: &[u8], h: &[u8]) -> (Vec<u8>, Vec<u8>) {
+fn derive_keys(m_prime: &[u8], h: &[u8]) -> (Zeroizing<[u8; 32]>, Zeroizing<[u8; 32]>) {
     let mut hasher = Sha3_512::default();
     hasher.update(m_prime);
     hasher.update(h);
-    let binding = hasher.finalize().as_slice().to_vec();
-    let (k_prime, r_prime) = binding.split_at(32);
-    (k_prime.to_vec(), r_prime.to_vec())
+    let mut binding = hasher.finalize();
+    let (k_prime, r_prime) = binding.as_slice().split_at(32);
+    let mut keys = (Zeroizing::new([0u8; 32]), Zeroizing::new([0u8; 32]));
+    keys.0.copy_from_slice(k_prime);
+    keys.1.copy_from_slice(r_prime);
+    binding.as_mut_slice().zeroize();
+    keys
 }
 
-// Compute K̄ using SHA3-512 hasher
-fn compute_k_bar(z: &[u8], c: &[u8]) -> Vec<u8> {
-    let mut hasher = Sha3_512::default();
-    hasher.update(z);
-    hasher.update(c);
-    hasher.finalize().as_slice()[0..32].to_vec()
+// K̄ = J(z || c)
+fn compute_k_bar(z: &[u8], c: &[u8]) -> Zeroizing<[u8; 32]> {
+    Zeroizing::new(j(z, c))
 }
 
 // FIPS 203 Section 5.3 Algorithm 14
 // Uses the decryption key to decrypt a ciphertext.
-fn k_pke_decrypt<P: ParameterSet>(dk_pke: &[u8], c: &[u8]) -> Result<Vec<u8>> {
-    let encode_du_size = <<P as ParameterSet>::Du as EncodingSize>::EncodedPolynomialSize::USIZE;
+fn k_pke_decrypt<P: ParameterSet>(dk_pke: &[u8], c: &[u8]) -> Result<Message> {
     let mut slice = c;
     let mut u = Array::<RingElement, P::K>::default();
     for i in 0..P::K::to_usize() {
-        let (current, next) = slice.split_at(encode_du_size);
-        let mut f: RingElement = Encode::<P::Du>::decode(current);
-        f.decompress::<P::Du>();
-
-        u[i] = f;
+        let (current, next) = slice.split_at(P::BYTES_PER_U_POLY);
+        let f: RingElement = Encode::<P::Du>::decode(current);
+        u[i] = f.decompress_copy::<P::Du>();
         slice = next;
     }
 
     let mut slice = dk_pke;
     let mut s_hat = Array::<NttElement, P::K>::default();
     for i in 0..P::K::to_usize() {
-        let (current, next) = slice.split_at(ENCODE_12);
-        let f = NttElement::byte_decode_12(current)?;
+        let (current, next) = slice.split_at(P::BYTES_PER_T_POLY);
+        // `dk_pke` is secret: do not report which coefficient was bad.
+        let f = NttElement::byte_decode_12(current).map_err(|_| KemError::InvalidInput)?;
         s_hat[i] = f;
         slice = next;
     }
 
-    let c2_size = <<P as ParameterSet>::Dv as EncodingSize>::EncodedPolynomialSize::USIZE;
-    let mut v: RingElement = Encode::<P::Dv>::decode(&c[c.len() - c2_size..c.len()]);
+    let mut v: RingElement = Encode::<P::Dv>::decode(&c[c.len() - P::BYTES_PER_V_POLY..]);
     v.decompress::<P::Dv>();
 
     let mut y = RingElement::zero();
@@ -114,7 +179,92 @@ fn k_pke_decrypt<P: ParameterSet>(dk_pke: &[u8], c: &[u8]) -> Result<Vec<u8>> {
         y += (s_hat[i] * u[i].into()).into();
     }
 
-    let mut w = v - y;
-    let s = Encode::<U1>::encode(w.compress::<U1>());
-    Ok(s)
+    let w = v - y;
+    Ok(Message::from(ring_element_to_message(&w)))
+}
+
+// These tests run ML-KEM-768 and ML-KEM-1024, which `blake3-xof` rejects.
+#[cfg(all(test, not(feature = "blake3-xof")))]
+mod tests {
+    use super::unpack_dk;
+    use crate::{
+        constants::parameter_sets::{ParameterSet, KEM_1024, KEM_512, KEM_768},
+        fips203::keygen::keygen_from_prf_output,
+    };
+    use hybrid_array::typenum::Unsigned;
+
+    fn check<P: ParameterSet>() {
+        let (pk, sk) = keygen_from_prf_output::<P>(&[1u8; 64]);
+        let parts = unpack_dk::<P>(&sk.dk);
+        assert_eq!(parts.dk_pke.len(), P::BYTES_PER_T_POLY * P::K::USIZE);
+        assert_eq!(parts.ek_pke.len(), P::PUBLIC_KEY_SIZE);
+        assert_eq!(parts.ek_pke, &pk.ek[..]);
+        assert_eq!(parts.h.len(), 32);
+        assert_eq!(parts.z, &[1u8; 32]);
+    }
+
+    #[test]
+    fn decaps_into_matches_decaps() {
+        use super::{mlkem_decaps, mlkem_decaps_into};
+        use crate::fips203::encrypt::mlkem_encaps;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let (pk, sk) = keygen_from_prf_output::<KEM_512>(&[5u8; 64]);
+        let mut rng = StdRng::seed_from_u64(1185);
+        let (k, c) = mlkem_encaps::<KEM_512, _>(&pk.ek, &mut rng).unwrap();
+
+        let mut out = [0u8; 32];
+        mlkem_decaps_into::<KEM_512>(&c, &sk.dk, &mut out).unwrap();
+        assert_eq!(&out, k.as_bytes());
+        assert_eq!(
+            &out,
+            mlkem_decaps::<KEM_512>(&c, &sk.dk).unwrap().as_bytes()
+        );
+    }
+
+    #[test]
+    fn bad_dk_coefficient_is_not_reported() {
+        use super::mlkem_decaps;
+        use crate::{error::KemError, fips203::encrypt::mlkem_encaps};
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let (pk, sk) = keygen_from_prf_output::<KEM_512>(&[5u8; 64]);
+        let mut rng = StdRng::seed_from_u64(1131);
+        let (_, c) = mlkem_encaps::<KEM_512, _>(&pk.ek, &mut rng).unwrap();
+
+        let mut dk = sk.dk.to_vec();
+        dk[3..6].fill(0xff);
+        assert_eq!(
+            mlkem_decaps::<KEM_512>(&c, &dk).unwrap_err(),
+            KemError::InvalidInput
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn corrupted_ek_hash_is_an_error_in_debug_builds() {
+        use super::mlkem_decaps;
+        use crate::{error::KemError, fips203::encrypt::mlkem_encaps};
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let (pk, sk) = keygen_from_prf_output::<KEM_768>(&[6u8; 64]);
+        let mut rng = StdRng::seed_from_u64(1094);
+        let (_, c) = mlkem_encaps::<KEM_768, _>(&pk.ek, &mut rng).unwrap();
+
+        // the stored H(ek) sits just before the last 32 bytes (z)
+        let mut dk = sk.dk.to_vec();
+        let h_at = dk.len() - 64;
+        dk[h_at] ^= 1;
+        assert_eq!(
+            mlkem_decaps::<KEM_768>(&c, &dk).unwrap_err(),
+            KemError::InvalidInput
+        );
+    }
+
+    #[test]
+    fn unpack_dk_field_lengths() {
+        check::<KEM_512>();
+        check::<KEM_768>();
+        check::<KEM_1024>();
+    }
 }
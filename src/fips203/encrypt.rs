@@ -1,33 +1,81 @@
+use super::{
+    keygen::{unpack_ek, KEMPublicKey},
+    primitives::h,
+    shared_secret::SharedSecret,
+};
 use crate::{
-    constants::{ml_kem_constants::ENCODE_12, parameter_sets::ParameterSet},
+    constants::parameter_sets::ParameterSet,
     error::{KemError, Result},
     math::{
-        encoding::{Compress, Encode},
+        encoding::Encode,
+        kvec::KVec,
+        matrix::AHatMatrix,
         ntt_element::NttElement,
-        ring_element::RingElement,
+        ring_element::{message_to_ring_element, RingElement},
+        util::ct_bytes_equal,
     },
 };
 use alloc::vec::Vec;
 use hybrid_array::{typenum::Unsigned, Array};
 use rand_core::{CryptoRng, RngCore};
 use sha3::{Digest, Sha3_512};
-use subtle::ConstantTimeEq;
-use typenum::U1;
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
 
 /// Encapsulation with provided RNG
 ///
 /// # Security
-/// 
+///
 /// This function performs constant-time comparisons and zeroizes sensitive
 /// intermediate values. The RNG must implement `CryptoRng` for security.
-#[allow(non_snake_case)]
+#[must_use = "the shared secret and ciphertext are the only outputs"]
 pub fn mlkem_encaps<P: ParameterSet, R: RngCore + CryptoRng>(
     ek: &[u8],
     rng: &mut R,
-) -> Result<(Vec<u8>, Vec<u8>)> {
+) -> Result<(SharedSecret, Vec<u8>)> {
+    check_ek::<P>(ek)?;
+    mlkem_encaps_unchecked::<P, R>(ek, rng)
+}
+
+/// Same as [`mlkem_encaps`], taking the typed [`KEMPublicKey`] instead of
+/// its raw bytes.
+#[must_use = "the shared secret and ciphertext are the only outputs"]
+pub fn mlkem_encaps_from_ek<P: ParameterSet, R: RngCore + CryptoRng>(
+    ek: &KEMPublicKey<P>,
+    rng: &mut R,
+) -> Result<(SharedSecret, Vec<u8>)> {
+    mlkem_encaps::<P, R>(&ek.ek, rng)
+}
+
+/// An encapsulation key that has already passed the FIPS 203 input checks.
+///
+/// Validation happens once in [`ValidatedPublicKey::new`], so repeated
+/// encapsulations to the same key skip the re-encoding check.
+#[derive(Debug, Clone)]
+pub struct ValidatedPublicKey<P: ParameterSet> {
+    pk: KEMPublicKey<P>,
+}
+
+impl<P: ParameterSet> ValidatedPublicKey<P> {
+    /// Runs the type and modulus checks on `pk`.
+    pub fn new(pk: KEMPublicKey<P>) -> Result<Self> {
+        check_ek::<P>(&pk.ek)?;
+        Ok(ValidatedPublicKey { pk })
+    }
+
+    pub fn public_key(&self) -> &KEMPublicKey<P> {
+        &self.pk
+    }
+
+    /// Encapsulates to the validated key without repeating the input checks.
+    pub fn encaps<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<(SharedSecret, Vec<u8>)> {
+        mlkem_encaps_unchecked::<P, R>(&self.pk.ek, rng)
+    }
+}
+
+// FIPS 203 Section 6.2, input checks on the encapsulation key
+fn check_ek<P: ParameterSet>(ek: &[u8]) -> Result<()> {
     let k = P::K::to_usize();
-    let ek_pke_size = ENCODE_12 * k;
+    let ek_pke_size = P::BYTES_PER_T_POLY * k;
 
     // Step 1. (Type check) Validate the key length
     if ek.len() != ek_pke_size + 32 {
@@ -37,84 +85,123 @@ pub fn mlkem_encaps<P: ParameterSet, R: RngCore + CryptoRng>(
     // Step 2. modulus check ek~ <- ByteEncode12(ByteDecode12(ek))
     // Using constant-time comparison to prevent timing attacks
     let mut ek_reencoded = Vec::with_capacity(ek_pke_size);
-    for i in 0..k {
-        let poly_slice = &ek[i * ENCODE_12..(i + 1) * ENCODE_12];
-        let decoded = NttElement::byte_decode_12(poly_slice)?;
+    let (t_bytes, _) = unpack_ek::<P>(ek);
+    for poly_slice in t_bytes.chunks_exact(P::BYTES_PER_T_POLY) {
+        let decoded = NttElement::byte_decode_12(poly_slice).map_err(|_| KemError::InvalidInput)?;
         ek_reencoded = decoded.byte_encode_12(ek_reencoded);
     }
 
     // Constant-time comparison
-    let comparison = ek_reencoded.ct_eq(&ek[0..ek_pke_size]);
-    if comparison.unwrap_u8() != 1 {
+    if !ct_bytes_equal(&ek_reencoded, t_bytes) {
         // Zeroize before returning error
         ek_reencoded.zeroize();
         return Err(KemError::InvalidInput);
     }
     ek_reencoded.zeroize();
+    Ok(())
+}
+
+/// Encapsulation without the input checks on `ek`.
+///
+/// Only reachable through [`ValidatedPublicKey::encaps`] or after
+/// [`check_ek`] has passed.
+pub(crate) fn mlkem_encaps_unchecked<P: ParameterSet, R: RngCore + CryptoRng>(
+    ek: &[u8],
+    rng: &mut R,
+) -> Result<(SharedSecret, Vec<u8>)> {
+    // Secret intermediates are wrapped in `Zeroizing` so they are cleared
+    // on every exit path, including the early return below.
 
     // Step 3. Generate 32 random bytes (see Section 3.3)
-    let mut m = [0_u8; 32];
-    rng.fill_bytes(&mut m);
+    let mut m = Zeroizing::new([0_u8; 32]);
+    rng.fill_bytes(m.as_mut());
+    // An all-zero draw almost certainly means a failed RNG
+    if ct_bytes_equal(m.as_ref(), &[0u8; 32]) {
+        return Err(KemError::InsufficientEntropy);
+    }
 
     // Step 4. Compute hash of encryption key
-    let h_ek = hash_to_slice(ek, 32);
+    let h_ek = h(ek);
 
     // Step 5. Concatenate m and h_ek, and hash to derive K and r
-    let (K, mut r) = derive_keys(&m, &h_ek);
+    let DerivationOutput {
+        shared_secret,
+        randomness,
+    } = derive_keys(&m, &h_ek);
 
     // Step 6. Encrypt the message
-    let c = k_pke_encrypt::<P>(ek, &m, &r)?;
-
-    // Zeroize sensitive intermediate values
-    m.zeroize();
-    r.zeroize();
+    let c = k_pke_encrypt::<P>(ek, m.as_ref(), randomness.as_ref())?;
 
-    Ok((K, c))
+    Ok((shared_secret, c))
 }
 
-fn hash_to_slice(data: &[u8], slice_size: usize) -> Vec<u8> {
-    let mut hasher = Sha3_512::default();
-    hasher.update(data);
-    hasher.finalize().as_slice()[0..slice_size].to_vec()
+/// `(K, r) = G(m || H(ek))`. Both halves are wiped on drop, so nothing is
+/// left behind if encryption fails.
+struct DerivationOutput {
+    shared_secret: SharedSecret,
+    /// The 32-byte `r` passed to `k_pke_encrypt`.
+    randomness: Zeroizing<[u8; 32]>,
 }
 
-#[allow(non_snake_case)]
-fn derive_keys(m: &[u8; 32], h_ek: &[u8]) -> (Vec<u8>, Vec<u8>) {
+fn derive_keys(m: &[u8; 32], h_ek: &[u8]) -> DerivationOutput {
     let mut hasher = Sha3_512::default();
     hasher.update(m);
     hasher.update(h_ek);
-    let binding = hasher.finalize();
-    let (K, r) = binding.as_slice().split_at(32);
-    (K.to_vec(), r.to_vec())
+    let mut binding = hasher.finalize();
+    let (k, r) = binding.as_slice().split_at(32);
+    let mut key = Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(k);
+    let mut randomness = Zeroizing::new([0u8; 32]);
+    randomness.copy_from_slice(r);
+    let out = DerivationOutput {
+        shared_secret: SharedSecret::from(*key),
+        randomness,
+    };
+    binding.as_mut_slice().zeroize();
+    out
 }
 
+// FIPS 203 Section 5.2 Algorithm 13
 pub(crate) fn k_pke_encrypt<P: ParameterSet>(
     ek_pke: &[u8],
     m: &[u8],
     rand: &[u8],
 ) -> Result<Vec<u8>> {
+    let mut c = alloc::vec![0u8; P::CIPHERTEXT_SIZE];
+    k_pke_encrypt_into::<P>(ek_pke, m, rand, &mut c)?;
+    Ok(c)
+}
+
+// Same as `k_pke_encrypt`, writing the ciphertext into `c`, which must be
+// `P::CIPHERTEXT_SIZE` bytes. Does not allocate, so decapsulation
+// can re-encrypt into a stack buffer.
+pub(crate) fn k_pke_encrypt_into<P: ParameterSet>(
+    ek_pke: &[u8],
+    m: &[u8],
+    rand: &[u8],
+    c: &mut [u8],
+) -> Result<()> {
+    let () = P::XOF_SECURITY_CHECK;
+    debug_assert_eq!(c.len(), P::CIPHERTEXT_SIZE);
     let k = P::K::to_usize();
     let mut n = 0;
-    let mut t_hat = Array::<NttElement, P::K>::default();
-
-    for i in 0..t_hat.len() {
-        t_hat[i] = NttElement::byte_decode_12(&ek_pke[i * ENCODE_12..(i + 1) * ENCODE_12])?;
+    let mut t_hat = KVec::<P>::default();
+
+    let (t_bytes, rho) = unpack_ek::<P>(ek_pke);
+    for (t, bytes) in t_hat
+        .iter_mut()
+        .zip(t_bytes.chunks_exact(P::BYTES_PER_T_POLY))
+    {
+        *t = NttElement::byte_decode_12(bytes)?;
     }
 
-    let rho: &[u8] = &ek_pke[ENCODE_12 * k..(ENCODE_12 * k) + 32];
-
     // Generate the matrix a_hat^T
-    let mut a_hat_transpose = Array::<NttElement, P::KSquared>::default();
-    for i in 0..k {
-        for j in 0..k {
-            a_hat_transpose[i * k + j] = NttElement::sample_ntt(rho, i, j);
-        }
-    }
+    let a_hat_transpose = AHatMatrix::<P>::from_rho(rho).transpose();
 
-    // generate r, run ntt k times (uses EtaTwo)
-    let mut r_hat = Array::<NttElement, P::K>::default();
+    // generate r, run ntt k times (uses EtaOne)
+    let mut r_hat = KVec::<P>::default();
     for r_elem in r_hat.iter_mut() {
-        *r_elem = RingElement::sample_poly_cbd::<P::EtaTwo>(rand, n).into();
+        *r_elem = RingElement::sample_poly_cbd::<P::EtaOne>(rand, n).into();
         n += 1;
     }
 
@@ -128,36 +215,25 @@ pub(crate) fn k_pke_encrypt<P: ParameterSet>(
     // sample e2 (uses EtaTwo)
     let e2: RingElement = RingElement::sample_poly_cbd::<P::EtaTwo>(rand, n);
 
-    let mut u: Vec<RingElement> = e_1
-        .iter()
-        .enumerate()
-        .map(|(i, e1_elem)| {
-            let sum: RingElement = (0..k)
-                .map(|j| (a_hat_transpose[i * k + j] * r_hat[j]).into())
-                .sum();
-            *e1_elem + sum
-        })
-        .collect();
-
-    let mut mu: RingElement = Encode::<U1>::decode(m);
-    mu.decompress::<U1>();
-
-    let mut v = NttElement::zero();
-    for i in 0..t_hat.len() {
-        v += t_hat[i] * r_hat[i];
+    // u = NTT^-1(A^T o r_hat) + e1
+    let a_t_r = &a_hat_transpose * &r_hat;
+    let mut u = Array::<RingElement, P::K>::default();
+    for ((u_elem, e1_elem), a_t_r_elem) in u.iter_mut().zip(e_1.iter()).zip(a_t_r.iter()) {
+        *u_elem = *e1_elem + RingElement::from(*a_t_r_elem);
     }
-    let mut v = v.ntt_inv();
+
+    let mu = message_to_ring_element(m.try_into().map_err(|_| KemError::InvalidInput)?);
+
+    let mut v = (&t_hat * &r_hat).ntt_inv();
     v += e2;
     v += mu;
 
-    let mut c: Vec<u8> = Vec::new();
-
-    for ring in u.iter_mut() {
-        let bytes = &mut Encode::<P::Du>::encode(ring.compress::<P::Du>());
-        c.append(bytes);
+    let (c_1, c_2) = c.split_at_mut(P::BYTES_PER_U_POLY * k);
+    for (ring, bytes) in u.iter().zip(c_1.chunks_exact_mut(P::BYTES_PER_U_POLY)) {
+        Encode::<P::Du>::encode_into(&ring.compress_copy::<P::Du>(), bytes);
     }
 
-    c.append(&mut Encode::<P::Dv>::encode(v.compress::<P::Dv>()));
+    Encode::<P::Dv>::encode_into(&v.compress_copy::<P::Dv>(), c_2);
 
-    Ok(c)
+    Ok(())
 }
@@ -0,0 +1,132 @@
+//! A trait over keygen, encapsulation and decapsulation, so protocol code
+//! can be written against any KEM (including test doubles).
+
+use super::{
+    decrypt::mlkem_decaps,
+    encrypt::mlkem_encaps,
+    keygen::{ml_kem_keygen, KEMPrivateKey, KEMPublicKey},
+    shared_secret::SharedSecret,
+};
+#[cfg(not(feature = "blake3-xof"))]
+use crate::constants::parameter_sets::{KEM_1024, KEM_768};
+use crate::{
+    constants::parameter_sets::{ParameterSet, KEM_512},
+    error::Result,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+use rand_core::{CryptoRng, CryptoRngCore, RngCore};
+
+pub trait KeyEncapsulationMechanism {
+    type PublicKey;
+    type SecretKey;
+    type Ciphertext;
+    type SharedSecret;
+
+    /// Generates a fresh key pair.
+    fn keygen<R: RngCore + CryptoRng>(rng: &mut R) -> Result<(Self::PublicKey, Self::SecretKey)>;
+
+    /// Encapsulates a fresh shared secret to `pk`.
+    fn encaps<R: RngCore + CryptoRng>(
+        pk: &Self::PublicKey,
+        rng: &mut R,
+    ) -> Result<(Self::SharedSecret, Self::Ciphertext)>;
+
+    /// Recovers the shared secret from `ct`.
+    fn decaps(sk: &Self::SecretKey, ct: &Self::Ciphertext) -> Result<Self::SharedSecret>;
+}
+
+/// ML-KEM for the parameter set `P`, e.g. `MlKem<KEM_768>`.
+pub struct MlKem<P: ParameterSet>(PhantomData<P>);
+
+impl<P: ParameterSet> KeyEncapsulationMechanism for MlKem<P> {
+    type PublicKey = KEMPublicKey<P>;
+    type SecretKey = KEMPrivateKey<P>;
+    type Ciphertext = Vec<u8>;
+    type SharedSecret = SharedSecret;
+
+    fn keygen<R: RngCore + CryptoRng>(rng: &mut R) -> Result<(Self::PublicKey, Self::SecretKey)> {
+        ml_kem_keygen::<P, R>(rng)
+    }
+
+    fn encaps<R: RngCore + CryptoRng>(
+        pk: &Self::PublicKey,
+        rng: &mut R,
+    ) -> Result<(Self::SharedSecret, Self::Ciphertext)> {
+        mlkem_encaps::<P, R>(&pk.ek, rng)
+    }
+
+    fn decaps(sk: &Self::SecretKey, ct: &Self::Ciphertext) -> Result<Self::SharedSecret> {
+        mlkem_decaps::<P>(ct, &sk.dk)
+    }
+}
+
+/// Object-safe, byte-oriented view of a KEM for choosing the algorithm at
+/// runtime. Keys and ciphertexts are passed as their encoded bytes.
+///
+/// [`KeyEncapsulationMechanism`] has generic methods and no receiver, so it
+/// cannot be used as `dyn`; this trait is its counterpart for that case.
+pub trait DynKem {
+    /// Name of the parameter set, e.g. `"ML-KEM-768"`.
+    fn label(&self) -> &'static str;
+
+    /// Returns `(ek, dk)`.
+    fn keygen(&self, rng: &mut dyn CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    fn encaps(&self, ek: &[u8], rng: &mut dyn CryptoRngCore) -> Result<(SharedSecret, Vec<u8>)>;
+
+    /// # Errors
+    ///
+    /// Returns [`KemError::InvalidInput`] if `dk` or `ct` has the wrong
+    /// length for this parameter set.
+    ///
+    /// [`KemError::InvalidInput`]: crate::error::KemError::InvalidInput
+    fn decaps(&self, dk: &[u8], ct: &[u8]) -> Result<SharedSecret>;
+}
+
+impl<P: ParameterSet> DynKem for MlKem<P> {
+    fn label(&self) -> &'static str {
+        P::LABEL
+    }
+
+    fn keygen(&self, mut rng: &mut dyn CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk, sk) = ml_kem_keygen::<P, _>(&mut rng)?;
+        Ok((pk.ek.into(), sk.dk.to_vec()))
+    }
+
+    fn encaps(
+        &self,
+        ek: &[u8],
+        mut rng: &mut dyn CryptoRngCore,
+    ) -> Result<(SharedSecret, Vec<u8>)> {
+        mlkem_encaps::<P, _>(ek, &mut rng)
+    }
+
+    fn decaps(&self, dk: &[u8], ct: &[u8]) -> Result<SharedSecret> {
+        mlkem_decaps::<P>(ct, dk)
+    }
+}
+
+/// The parameter sets selectable through [`make_kem`].
+///
+/// With the `blake3-xof` feature only `MlKem512` is available, since
+/// BLAKE3 offers no more than 128-bit security.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnyKem {
+    MlKem512,
+    #[cfg(not(feature = "blake3-xof"))]
+    MlKem768,
+    #[cfg(not(feature = "blake3-xof"))]
+    MlKem1024,
+}
+
+/// Returns a boxed KEM for `variant`.
+pub fn make_kem(variant: AnyKem) -> Box<dyn DynKem> {
+    match variant {
+        AnyKem::MlKem512 => Box::new(MlKem::<KEM_512>(PhantomData)),
+        #[cfg(not(feature = "blake3-xof"))]
+        AnyKem::MlKem768 => Box::new(MlKem::<KEM_768>(PhantomData)),
+        #[cfg(not(feature = "blake3-xof"))]
+        AnyKem::MlKem1024 => Box::new(MlKem::<KEM_1024>(PhantomData)),
+    }
+}
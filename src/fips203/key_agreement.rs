@@ -0,0 +1,77 @@
+//! Mutual encapsulation between two parties that both hold KEM key pairs.
+//!
+//! ## Protocol
+//!
+//! 1. Each party calls [`key_agreement`] with the peer's public key. This
+//!    encapsulates to the peer and returns a [`PendingAgreement`] together
+//!    with a ciphertext.
+//! 2. Each party sends its ciphertext to the other in a companion message.
+//! 3. On receipt, each party calls [`PendingAgreement::finish`] with its own
+//!    private key and the peer's ciphertext. Both sides obtain
+//!    `K_a XOR K_b`, where `K_a` and `K_b` are the secrets encapsulated by
+//!    the two parties.
+//!
+//! The exchange must happen in two steps because neither ciphertext depends
+//! on the other, but each party needs both to derive the result.
+//!
+//! ## Requirements
+//!
+//! * Public keys must be authenticated out of band; this module provides no
+//!   authentication of its own.
+//! * A fresh [`PendingAgreement`] must be used for every exchange.
+//! * Callers that need to bind the result to the session should feed it,
+//!   together with both ciphertexts and public keys, through a KDF.
+
+use super::{
+    decrypt::mlkem_decaps,
+    encrypt::mlkem_encaps,
+    keygen::{KEMPrivateKey, KEMPublicKey},
+    shared_secret::SharedSecret,
+};
+use crate::{constants::parameter_sets::ParameterSet, error::Result};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+/// The local half of a mutual encapsulation, waiting for the peer's
+/// ciphertext.
+pub struct PendingAgreement<P: ParameterSet> {
+    k_out: SharedSecret,
+    _marker: PhantomData<P>,
+}
+
+/// Encapsulates to `peer_pk` and returns the pending state and the
+/// ciphertext to send to the peer.
+///
+/// # Errors
+///
+/// Returns an error if `peer_pk` fails the encapsulation key checks.
+pub fn key_agreement<P: ParameterSet, R: RngCore + CryptoRng>(
+    peer_pk: &KEMPublicKey<P>,
+    rng: &mut R,
+) -> Result<(PendingAgreement<P>, Vec<u8>)> {
+    let (k_out, c) = mlkem_encaps::<P, R>(&peer_pk.ek, rng)?;
+    let pending = PendingAgreement {
+        k_out,
+        _marker: PhantomData,
+    };
+    Ok((pending, c))
+}
+
+impl<P: ParameterSet> PendingAgreement<P> {
+    /// Decapsulates the peer's ciphertext with `my_sk` and XOR-combines the
+    /// result with the locally encapsulated secret.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decapsulation fails on malformed input.
+    pub fn finish(self, my_sk: &KEMPrivateKey<P>, peer_ct: &[u8]) -> Result<SharedSecret> {
+        let k_in = mlkem_decaps::<P>(peer_ct, &my_sk.dk)?;
+        let mut k = Zeroizing::new([0u8; 32]);
+        for ((out, a), b) in k.iter_mut().zip(self.k_out.as_bytes()).zip(k_in.as_bytes()) {
+            *out = a ^ b;
+        }
+        Ok(SharedSecret::from(*k))
+    }
+}
@@ -0,0 +1,109 @@
+//! Minimal text decoders for keys pasted from config files or environment
+//! variables. Only decoding is supported.
+
+use alloc::vec::Vec;
+
+/// Decodes a hex string (either case). Returns `None` on odd length or a
+/// non-hex character.
+pub(crate) fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let s = s.as_bytes();
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    s.chunks_exact(2)
+        .map(|pair| Some((hex_val(pair[0])? << 4) | hex_val(pair[1])?))
+        .collect()
+}
+
+fn hex_val(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decodes standard (RFC 4648) padded base64, ignoring ASCII whitespace.
+pub(crate) fn decode_base64(s: &str) -> Option<Vec<u8>> {
+    let chars: Vec<u8> = s.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    if chars.is_empty() || !chars.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(chars.len() / 4 * 3);
+    let last = chars.len() / 4 - 1;
+    for (i, quad) in chars.chunks_exact(4).enumerate() {
+        let pad = quad.iter().rev().take_while(|&&c| c == b'=').count();
+        if pad > 2 || (pad > 0 && i != last) {
+            return None;
+        }
+
+        let mut acc = 0u32;
+        for &c in &quad[..4 - pad] {
+            acc = (acc << 6) | u32::from(base64_val(c)?);
+        }
+        acc <<= 6 * pad;
+
+        let bytes = acc.to_be_bytes();
+        out.extend_from_slice(&bytes[1..4 - pad]);
+    }
+    Some(out)
+}
+
+fn base64_val(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes the base64 body of a PEM block, ignoring the label.
+pub(crate) fn decode_pem(s: &str) -> Option<Vec<u8>> {
+    let mut lines = s.trim().lines();
+    if !lines.next()?.starts_with("-----BEGIN ") {
+        return None;
+    }
+
+    let mut body = alloc::string::String::new();
+    for line in lines {
+        if line.starts_with("-----END ") {
+            return decode_base64(&body);
+        }
+        body.push_str(line.trim());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_base64, decode_hex, decode_pem};
+
+    #[test]
+    fn hex() {
+        assert_eq!(decode_hex("00ffA1"), Some(alloc::vec![0x00, 0xff, 0xa1]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+
+    #[test]
+    fn base64() {
+        assert_eq!(decode_base64("TWFu"), Some(b"Man".to_vec()));
+        assert_eq!(decode_base64("TWE="), Some(b"Ma".to_vec()));
+        assert_eq!(decode_base64("TQ=="), Some(b"M".to_vec()));
+        assert_eq!(decode_base64("TQ==TWFu"), None);
+        assert_eq!(decode_base64("TWF"), None);
+    }
+
+    #[test]
+    fn pem() {
+        let pem =
+            "-----BEGIN ML-KEM PUBLIC KEY-----\nTWFu\nTWE=\n-----END ML-KEM PUBLIC KEY-----\n";
+        assert_eq!(decode_pem(pem), Some(b"ManMa".to_vec()));
+        assert_eq!(decode_pem("-----BEGIN X-----\nTWFu\n"), None);
+    }
+}
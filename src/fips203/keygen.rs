@@ -1,14 +1,146 @@
+use super::{
+    decrypt::unpack_dk,
+    key_format,
+    primitives::{g, h},
+};
 use crate::{
-    constants::{ml_kem_constants::ENCODE_12, parameter_sets::ParameterSet},
-    math::{ntt_element::NttElement, ring_element::RingElement},
+    constants::parameter_sets::ParameterSet,
+    error::{KemError, Result},
+    math::{kvec::KVec, matrix::AHatMatrix, ring_element::RingElement, util::ct_bytes_equal},
 };
 use alloc::vec::Vec;
-use core::fmt;
-use hybrid_array::{typenum::Unsigned, Array};
+use core::{fmt, marker::PhantomData, ops::Deref};
+use hybrid_array::typenum::Unsigned;
 use rand_core::{CryptoRng, RngCore};
-use serde::{Deserialize, Serialize};
-use sha3::{Digest, Sha3_512};
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use serde::{Deserialize, Serialize, Serializer};
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake256,
+};
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// The encoded encapsulation key `ek`, tagged with its parameter set.
+///
+/// Converting from bytes checks the length against `P`, so a KEM-512 key
+/// cannot end up where a KEM-768 key is expected. Dereferences to the raw
+/// bytes.
+///
+/// Serializes as its bytes. Deserializing goes through
+/// `TryFrom<Vec<u8>>`, so it runs the same length check.
+#[derive(Deserialize)]
+#[serde(try_from = "Vec<u8>", bound = "")]
+pub struct EncapsKey<P: ParameterSet>(Vec<u8>, PhantomData<P>);
+
+/// The encoded decapsulation key `dk`, tagged with its parameter set.
+///
+/// Zeroized on drop, redacted from `Debug`, and compared in constant time.
+/// Serialized and length-checked like [`EncapsKey`].
+#[derive(Deserialize, Zeroize, ZeroizeOnDrop)]
+#[serde(try_from = "Vec<u8>", bound = "")]
+pub struct DecapsKey<P: ParameterSet>(Vec<u8>, #[zeroize(skip)] PhantomData<P>);
+
+macro_rules! impl_key_bytes {
+    ($key:ident, $size:ident) => {
+        impl<P: ParameterSet> $key<P> {
+            // Wraps bytes produced by keygen, which always have the right
+            // length.
+            fn from_generated(bytes: Vec<u8>) -> Self {
+                debug_assert_eq!(bytes.len(), P::$size);
+                $key(bytes, PhantomData)
+            }
+        }
+
+        impl<P: ParameterSet> Clone for $key<P> {
+            fn clone(&self) -> Self {
+                $key(self.0.clone(), PhantomData)
+            }
+        }
+
+        // Not `#[serde(into = "Vec<u8>")]`: that would clone the bytes
+        // into a `Vec` nobody wipes.
+        impl<P: ParameterSet> Serialize for $key<P> {
+            fn serialize<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> core::result::Result<S::Ok, S::Error> {
+                self.0.serialize(serializer)
+            }
+        }
+
+        impl<P: ParameterSet> Deref for $key<P> {
+            type Target = [u8];
+
+            fn deref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl<P: ParameterSet> AsRef<[u8]> for $key<P> {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        /// # Errors
+        ///
+        /// Returns [`KemError::InvalidInput`] if the length does not match
+        /// the parameter set.
+        impl<P: ParameterSet> TryFrom<Vec<u8>> for $key<P> {
+            type Error = KemError;
+
+            fn try_from(mut bytes: Vec<u8>) -> Result<Self> {
+                if bytes.len() != P::$size {
+                    // may still be a secret key, just for another `P`
+                    bytes.zeroize();
+                    return Err(KemError::InvalidInput);
+                }
+                Ok($key(bytes, PhantomData))
+            }
+        }
+    };
+}
+
+impl_key_bytes!(EncapsKey, PUBLIC_KEY_SIZE);
+impl_key_bytes!(DecapsKey, PRIVATE_KEY_SIZE);
+
+impl<P: ParameterSet> From<EncapsKey<P>> for Vec<u8> {
+    fn from(ek: EncapsKey<P>) -> Self {
+        ek.0
+    }
+}
+
+/// Hands out the secret bytes; the caller becomes responsible for wiping
+/// them.
+impl<P: ParameterSet> From<DecapsKey<P>> for Vec<u8> {
+    fn from(mut dk: DecapsKey<P>) -> Self {
+        core::mem::take(&mut dk.0)
+    }
+}
+
+impl<P: ParameterSet> PartialEq for EncapsKey<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<P: ParameterSet> PartialEq for DecapsKey<P> {
+    fn eq(&self, other: &Self) -> bool {
+        ct_bytes_equal(&self.0, &other.0)
+    }
+}
+
+impl<P: ParameterSet> fmt::Debug for EncapsKey<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EncapsKey").field(&self.0).finish()
+    }
+}
+
+impl<P: ParameterSet> fmt::Debug for DecapsKey<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DecapsKey").field(&"<redacted>").finish()
+    }
+}
 
 /// Represents a private key for Key Encapsulation Mechanism (KEM).
 ///
@@ -17,20 +149,43 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 ///
 /// ## Fields
 ///
-/// * `dk: Vec<u8>` - The private decryption key data,
+/// * `dk: DecapsKey<P>` - The private decryption key data,
 ///   essential for decrypting the KEM ciphertext.
 ///
+/// The parameter set `P` is carried at the type level only, so a key from
+/// one parameter set cannot be passed where another is expected.
+///
 /// ## Security
 ///
 /// This type implements `ZeroizeOnDrop` to ensure that private key material
 /// is securely erased from memory when dropped. The Debug and Display
 /// implementations are redacted to prevent accidental leakage of secret material.
 #[derive(Serialize, Deserialize, Clone, Zeroize, ZeroizeOnDrop)]
-pub struct KEMPrivateKey {
-    pub dk: Vec<u8>,
+#[serde(bound = "")]
+pub struct KEMPrivateKey<P: ParameterSet> {
+    pub dk: DecapsKey<P>,
+}
+
+impl<P: ParameterSet> KEMPrivateKey<P> {
+    /// Recomputes `H(ek)` from the `ek` embedded in `dk` and compares it in
+    /// constant time with the stored hash.
+    ///
+    /// A mismatch means the key bytes were corrupted.
+    pub fn ek_hash_matches(&self) -> bool {
+        ek_hash_matches::<P>(&self.dk)
+    }
+}
+
+/// See [`KEMPrivateKey::ek_hash_matches`].
+pub(crate) fn ek_hash_matches<P: ParameterSet>(dk: &[u8]) -> bool {
+    if dk.len() != P::PRIVATE_KEY_SIZE {
+        return false;
+    }
+    let parts = unpack_dk::<P>(dk);
+    hash_ek(parts.ek_pke)[..].ct_eq(parts.h).into()
 }
 
-impl fmt::Debug for KEMPrivateKey {
+impl<P: ParameterSet> fmt::Debug for KEMPrivateKey<P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("KEMPrivateKey")
             .field("dk", &"<redacted>")
@@ -38,7 +193,7 @@ impl fmt::Debug for KEMPrivateKey {
     }
 }
 
-impl fmt::Display for KEMPrivateKey {
+impl<P: ParameterSet> fmt::Display for KEMPrivateKey<P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "KEMPrivateKey {{ dk: <redacted> }}")
     }
@@ -56,11 +211,97 @@ impl fmt::Display for KEMPrivateKey {
 ///
 /// * `rand_bytes: [u8; 32]` - Random bytes used to seed KEM
 ///   operations, ensuring the uniqueness and security of the public key.
-/// * `ek: Vec<u8>` - The public encryption key data,
+/// * `ek: EncapsKey<P>` - The public encryption key data,
 ///   used to encrypt data in the KEM scheme.
+///
+/// As with [`KEMPrivateKey`], `P` ties the key to its parameter set.
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct KEMPublicKey {
-    pub ek: Vec<u8>,
+#[serde(bound = "")]
+pub struct KEMPublicKey<P: ParameterSet> {
+    pub ek: EncapsKey<P>,
+}
+
+/// Parses an encapsulation key from text.
+///
+/// The format is detected automatically: a PEM block if the string starts
+/// with `-----BEGIN`, hex if it is a hex string of the expected length, and
+/// base64 otherwise. The decoded key must be exactly `384 * k + 32` bytes.
+impl<P: ParameterSet> TryFrom<&str> for KEMPublicKey<P> {
+    type Error = KemError;
+
+    fn try_from(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let expected = P::PUBLIC_KEY_SIZE;
+
+        let ek = if s.starts_with("-----BEGIN") {
+            key_format::decode_pem(s)
+        } else if s.len() == 2 * expected && s.bytes().all(|c| c.is_ascii_hexdigit()) {
+            key_format::decode_hex(s)
+        } else {
+            key_format::decode_base64(s)
+        }
+        .ok_or(KemError::InvalidInput)?;
+
+        Ok(KEMPublicKey {
+            ek: EncapsKey::try_from(ek)?,
+        })
+    }
+}
+
+/// An encapsulation key split into its two components: the encoded
+/// `t_hat` polynomials and the matrix seed `rho`.
+///
+/// The bytes are the same as in [`KEMPublicKey`]; this type only makes the
+/// layout `ByteEncode12(t_hat) || rho` explicit.
+#[derive(Debug, Clone)]
+pub struct CompressedPublicKey<P: ParameterSet> {
+    t_bytes: Vec<u8>,
+    rho: [u8; 32],
+    _ph: PhantomData<P>,
+}
+
+impl<P: ParameterSet> CompressedPublicKey<P> {
+    /// Splits `ek` into `t_hat` bytes and `rho`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KemError::InvalidInput`] if `ek` is not `384 * k + 32`
+    /// bytes long.
+    pub fn from_ek(ek: &[u8]) -> Result<Self> {
+        if ek.len() != P::PUBLIC_KEY_SIZE {
+            return Err(KemError::InvalidInput);
+        }
+        let (t_bytes, rho) = unpack_ek::<P>(ek);
+        Ok(CompressedPublicKey {
+            t_bytes: t_bytes.to_vec(),
+            rho: rho.try_into().map_err(|_| KemError::InvalidInput)?,
+            _ph: PhantomData,
+        })
+    }
+
+    pub fn t_bytes(&self) -> &[u8] {
+        &self.t_bytes
+    }
+
+    pub fn rho(&self) -> &[u8; 32] {
+        &self.rho
+    }
+}
+
+impl<P: ParameterSet> From<CompressedPublicKey<P>> for KEMPublicKey<P> {
+    fn from(pk: CompressedPublicKey<P>) -> Self {
+        KEMPublicKey {
+            ek: EncapsKey::from_generated(pack_ek(&pk.t_bytes, &pk.rho)),
+        }
+    }
+}
+
+impl<P: ParameterSet> TryFrom<KEMPublicKey<P>> for CompressedPublicKey<P> {
+    type Error = KemError;
+
+    fn try_from(pk: KEMPublicKey<P>) -> Result<Self> {
+        CompressedPublicKey::from_ek(&pk.ek)
+    }
 }
 
 /// Generates a public-private key pair for use with the Key Encapsulation Mechanism (KEM).
@@ -72,6 +313,11 @@ pub struct KEMPublicKey {
 ///
 /// * `rng` - A cryptographically secure random number generator
 ///
+/// # Errors
+///
+/// Returns [`KemError::EntropyFailure`] if `rng.try_fill_bytes` fails, and
+/// [`KemError::InsufficientEntropy`] if it returns 32 zero bytes.
+///
 /// # Security
 ///
 /// This function zeroizes sensitive intermediate values. The RNG must implement
@@ -85,33 +331,183 @@ pub struct KEMPublicKey {
 /// use capy_kem::constants::parameter_sets::KEM_768;
 ///
 /// let mut rng = thread_rng();
-/// let (pk, sk) = ml_kem_keygen::<KEM_768>(&mut rng);
+/// let (pk, sk) = ml_kem_keygen::<KEM_768, _>(&mut rng)?;
 /// ```
+#[must_use = "the generated key pair is the only output"]
 pub fn ml_kem_keygen<P: ParameterSet, R: RngCore + CryptoRng>(
     rng: &mut R,
-) -> (KEMPublicKey, KEMPrivateKey) {
-    let mut z = [0u8; 32];
+) -> Result<(KEMPublicKey<P>, KEMPrivateKey<P>)> {
+    ml_kem_keygen_from_entropy::<P, R>(rng)
+}
+
+/// Generates `count` independent key pairs, e.g. to fill a key pool.
+///
+/// Each pair draws its own seed from `rng`, so the result is the same as
+/// calling [`ml_kem_keygen`] `count` times. Generation is sequential unless
+/// the `rayon` feature is enabled; then the seeds are still drawn in order
+/// from `rng`, and only the key generation from them runs on rayon's
+/// thread pool, so the output does not change.
+///
+/// # Errors
+///
+/// Stops at and returns the first error from [`ml_kem_keygen`].
+#[must_use = "the generated key pairs are the only output"]
+pub fn mlkem_keygen_batch<P: ParameterSet, R: RngCore + CryptoRng>(
+    count: usize,
+    rng: &mut R,
+) -> Result<Vec<(KEMPublicKey<P>, KEMPrivateKey<P>)>> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        let seeds = (0..count)
+            .map(|_| draw_seeds(rng))
+            .collect::<Result<Vec<_>>>()?;
+        // The typed keys are only `Send` if `P` is, so the workers return
+        // bytes and the keys are built here.
+        let keys: Vec<_> = seeds
+            .par_iter()
+            .map(|seeds| keygen_bytes::<P>(&seeds[..32], &seeds[32..]))
+            .collect();
+        Ok(keys.into_iter().map(wrap_keys).collect())
+    }
+    #[cfg(not(feature = "rayon"))]
+    (0..count).map(|_| ml_kem_keygen::<P, R>(rng)).collect()
+}
+
+/// A source of secret random bytes, for platforms that expose raw entropy
+/// (e.g. an HSM) rather than an RNG.
+///
+/// Every `RngCore + CryptoRng` is an `EntropySource`.
+pub trait EntropySource {
+    /// Fills `dest` entirely with fresh random bytes.
+    ///
+    /// # Errors
+    ///
+    /// Should return [`KemError::EntropyFailure`] if the source cannot
+    /// supply the bytes.
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()>;
+}
+
+impl<R: RngCore + CryptoRng> EntropySource for R {
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+        self.try_fill_bytes(dest)
+            .map_err(|_| KemError::EntropyFailure)
+    }
+}
+
+/// Same as [`ml_kem_keygen`], drawing the seed from any [`EntropySource`].
+///
+/// Draws 64 bytes and splits them into independent seeds `d || z`, as
+/// [`keygen_from_prf_output`] does.
+///
+/// # Errors
+///
+/// Propagates the error if `entropy` fails to produce the seed, and returns
+/// [`KemError::InsufficientEntropy`] if the seed is all zeros.
+#[must_use = "the generated key pair is the only output"]
+pub fn ml_kem_keygen_from_entropy<P: ParameterSet, E: EntropySource>(
+    entropy: &mut E,
+) -> Result<(KEMPublicKey<P>, KEMPrivateKey<P>)> {
+    let seeds = draw_seeds(entropy)?;
+    Ok(keygen_from_prf_output::<P>(&seeds))
+}
+
+/// Draws `d || z` from `entropy`, rejecting an all-zero draw.
+fn draw_seeds<E: EntropySource>(entropy: &mut E) -> Result<Zeroizing<[u8; 64]>> {
+    // d || z, zeroized on drop, including on any early return
+    let mut seeds = Zeroizing::new([0u8; 64]);
 
     // Generate randomness for the KEM
-    rng.fill_bytes(&mut z);
-    let (ek, mut dk) = k_pke_keygen::<P>(&z);
+    entropy.fill_bytes(seeds.as_mut())?;
+    // An all-zero draw almost certainly means a failed source
+    if ct_bytes_equal(seeds.as_ref(), &[0u8; 64]) {
+        return Err(KemError::InsufficientEntropy);
+    }
+
+    Ok(seeds)
+}
+
+/// Same as [`ml_kem_keygen`], but binds the key pair to `context`.
+///
+/// The seeds are `d || z = SHAKE-256(entropy || context || "keygen")` for 32
+/// bytes of fresh `entropy`, so key pairs for different contexts are domain
+/// separated even if the RNG output repeats. The context is not stored in
+/// the keys; nothing stops a key from being used in another context, this
+/// only ensures independent keys.
+///
+/// # Errors
+///
+/// Same as [`ml_kem_keygen`].
+#[must_use = "the generated key pair is the only output"]
+pub fn ml_kem_keygen_with_context<P: ParameterSet, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    context: &[u8],
+) -> Result<(KEMPublicKey<P>, KEMPrivateKey<P>)> {
+    let mut entropy = Zeroizing::new([0u8; 32]);
+    EntropySource::fill_bytes(rng, entropy.as_mut())?;
+    if ct_bytes_equal(entropy.as_ref(), &[0u8; 32]) {
+        return Err(KemError::InsufficientEntropy);
+    }
+
+    let mut hasher = Shake256::default();
+    hasher.update(entropy.as_ref());
+    hasher.update(context);
+    hasher.update(b"keygen");
+    let mut seeds = Zeroizing::new([0u8; 64]);
+    hasher.finalize_xof().read(seeds.as_mut());
+
+    Ok(keygen_from_prf_output::<P>(&seeds))
+}
+
+/// Generates a key pair from seeds the caller has already derived.
+///
+/// `g_output` is split as `d || z`: `d` seeds K-PKE key generation and `z`
+/// is stored in the decapsulation key for implicit rejection. This is meant
+/// for hybrid KEMs and protocols that derive both seeds from a master key
+/// with their own PRF.
+///
+/// # Security
+///
+/// The output is only as strong as `g_output`; it must be 64 bytes of
+/// uniformly random, secret key material.
+pub fn keygen_from_prf_output<P: ParameterSet>(
+    g_output: &[u8; 64],
+) -> (KEMPublicKey<P>, KEMPrivateKey<P>) {
+    let (d, z) = g_output.split_at(32);
+    keygen_internal::<P>(d, z)
+}
+
+fn keygen_internal<P: ParameterSet>(d: &[u8], z: &[u8]) -> (KEMPublicKey<P>, KEMPrivateKey<P>) {
+    wrap_keys(keygen_bytes::<P>(d, z))
+}
+
+/// `(ek, dk)` as encoded bytes.
+fn keygen_bytes<P: ParameterSet>(d: &[u8], z: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let (ek, mut dk) = k_pke_keygen::<P>(d);
 
     let h_ek = hash_ek(&ek);
 
     // Concatenate dk, ek, h_ek, and z into a single Vec<u8>
-    pack_dk(&mut dk, &ek, &h_ek, &z);
+    pack_dk(&mut dk, &ek, &h_ek, z);
 
-    // Zeroize sensitive intermediate value
-    z.zeroize();
+    (ek, dk)
+}
 
-    (KEMPublicKey { ek }, KEMPrivateKey { dk })
+fn wrap_keys<P: ParameterSet>((ek, dk): (Vec<u8>, Vec<u8>)) -> (KEMPublicKey<P>, KEMPrivateKey<P>) {
+    (
+        KEMPublicKey {
+            ek: EncapsKey::from_generated(ek),
+        },
+        KEMPrivateKey {
+            dk: DecapsKey::from_generated(dk),
+        },
+    )
 }
 
-/// Hashes the encryption key and returns the first 32 bytes of the hash
-fn hash_ek(ek: &[u8]) -> Vec<u8> {
-    let mut hasher = Sha3_512::default();
-    hasher.update(ek);
-    hasher.finalize().as_slice()[0..32].to_vec()
+/// `H(ek)`, stored in `dk` and mixed into the shared secret
+fn hash_ek(ek: &[u8]) -> [u8; 32] {
+    h(ek)
 }
 
 /// Concatenates dk, ek, h_ek, and z into dk
@@ -121,63 +517,267 @@ fn pack_dk(dk: &mut Vec<u8>, ek: &[u8], h_ek: &[u8], z: &[u8]) {
     dk.extend_from_slice(z);
 }
 
-fn k_pke_keygen<P: ParameterSet>(d: &[u8; 32]) -> (Vec<u8>, Vec<u8>) {
+/// Concatenates the encoded `t_hat` and `rho` into `ek`
+pub(crate) fn pack_ek(t_bytes: &[u8], rho: &[u8]) -> Vec<u8> {
+    let mut ek = Vec::with_capacity(t_bytes.len() + rho.len());
+    ek.extend_from_slice(t_bytes);
+    ek.extend_from_slice(rho);
+    ek
+}
+
+/// Splits `ek` into `(t_bytes, rho)`. Panics if `ek` is shorter than
+/// `P::PUBLIC_KEY_SIZE` bytes.
+pub(crate) fn unpack_ek<P: ParameterSet>(ek: &[u8]) -> (&[u8], &[u8]) {
+    let t_len = P::PUBLIC_KEY_SIZE - 32;
+    (&ek[..t_len], &ek[t_len..t_len + 32])
+}
+
+fn k_pke_keygen<P: ParameterSet>(d: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let () = P::XOF_SECURITY_CHECK;
     let k = P::K::to_usize();
-    let mut hasher = Sha3_512::default();
-    hasher.update(d);
-    let binding = hasher.finalize();
+
+    // (ρ, σ) <- G(d || k), with k as a single byte
+    let mut seed = Zeroizing::new([0u8; 33]);
+    seed[..32].copy_from_slice(d);
+    seed[32] = k as u8;
+    let mut binding = g(seed.as_ref());
     let b = binding.as_slice();
 
-    // (ρ, σ ) <- G(d)
     let rho: &[u8] = &b[0..32];
     let sigma = &b[32..64];
 
     let mut n = 0;
 
     // Generate the matrix a_hat (k * k elements)
-    let mut a_hat = Array::<NttElement, P::KSquared>::default();
-    for i in 0..k {
-        for j in 0..k {
-            // see: https://groups.google.com/a/list.nist.gov/g/pqc-forum/c/s-C-zIAeKfE/m/eZJmXYsSAQAJ?
-            a_hat[i * k + j] = NttElement::sample_ntt(rho, j, i);
-        }
-    }
+    // see: https://groups.google.com/a/list.nist.gov/g/pqc-forum/c/s-C-zIAeKfE/m/eZJmXYsSAQAJ?
+    let a_hat = AHatMatrix::<P>::from_rho(rho);
 
     // generate s (uses EtaOne)
-    let mut s_hat = Array::<NttElement, P::K>::default();
+    let mut s_hat = KVec::<P>::default();
     for s_elem in s_hat.iter_mut() {
         *s_elem = RingElement::sample_poly_cbd::<P::EtaOne>(sigma, n).into();
         n += 1;
     }
 
     // generate e (uses EtaOne)
-    let mut e_hat = Array::<NttElement, P::K>::default();
+    let mut e_hat = KVec::<P>::default();
     for e_elem in e_hat.iter_mut() {
         *e_elem = RingElement::sample_poly_cbd::<P::EtaOne>(sigma, n).into();
         n += 1;
     }
 
     // t_hat = A o s_hat + e_hat
-    let mut t = Array::<NttElement, P::K>::default();
-    for i in 0..t.len() {
-        t[i] = e_hat[i];
-        for j in 0..s_hat.len() {
-            t[i] += a_hat[i * k + j] * s_hat[j];
-        }
-    }
+    let t = &a_hat * &s_hat + e_hat;
+    debug_assert!(t.iter().all(|t_i| t_i.verify_ntt_consistency()));
 
-    // ByteEncode12(t_hat||rho)
-    let ek_pke_size = ENCODE_12 * k + 32;
-    let mut ek_pke: Vec<u8> = Vec::with_capacity(ek_pke_size);
+    // ByteEncode12(t_hat)||rho
+    let mut t_bytes: Vec<u8> = Vec::with_capacity(P::BYTES_PER_T_POLY * k);
     for item in t.iter() {
-        ek_pke = item.byte_encode_12(ek_pke);
+        t_bytes = item.byte_encode_12(t_bytes);
     }
-    ek_pke.extend_from_slice(rho);
+    let ek_pke = pack_ek(&t_bytes, rho);
 
-    let dk_pke_size = ENCODE_12 * k;
+    let dk_pke_size = P::BYTES_PER_T_POLY * k;
     let mut dk_pke: Vec<u8> = Vec::with_capacity(dk_pke_size);
     for item in s_hat.iter() {
         dk_pke = item.byte_encode_12(dk_pke);
     }
+
+    // σ is secret
+    binding.as_mut_slice().zeroize();
     (ek_pke, dk_pke)
 }
+
+// These tests run ML-KEM-768 and ML-KEM-1024, which `blake3-xof` rejects.
+#[cfg(all(test, not(feature = "blake3-xof")))]
+mod tests {
+    use super::{
+        k_pke_keygen, keygen_from_prf_output, ml_kem_keygen_from_entropy, mlkem_keygen_batch,
+        pack_ek, unpack_ek, CompressedPublicKey, EntropySource, KEMPublicKey,
+    };
+    use crate::{
+        constants::parameter_sets::KEM_768,
+        error::{KemError, Result},
+    };
+    use sha3::{Digest, Sha3_256};
+
+    #[test]
+    fn key_bytes_check_parameter_set() {
+        use super::{DecapsKey, EncapsKey};
+        use crate::constants::parameter_sets::KEM_512;
+        use alloc::vec::Vec;
+
+        let (pk, sk) = keygen_from_prf_output::<KEM_512>(&[3u8; 64]);
+        let ek: Vec<u8> = pk.ek.clone().into();
+        assert_eq!(EncapsKey::<KEM_512>::try_from(ek.clone()).unwrap(), pk.ek);
+        assert_eq!(
+            EncapsKey::<KEM_768>::try_from(ek).unwrap_err(),
+            KemError::InvalidInput
+        );
+        assert_eq!(
+            DecapsKey::<KEM_768>::try_from(sk.dk.to_vec()).unwrap_err(),
+            KemError::InvalidInput
+        );
+        assert_eq!(
+            DecapsKey::<KEM_512>::try_from(sk.dk.to_vec()).unwrap(),
+            sk.dk
+        );
+    }
+
+    #[test]
+    fn deserialize_checks_length() {
+        use super::{DecapsKey, EncapsKey};
+        use crate::constants::parameter_sets::KEM_512;
+        use alloc::vec::Vec;
+        use serde::{
+            de::value::{Error, SeqDeserializer},
+            Deserialize,
+        };
+
+        fn from_bytes<'de, T: Deserialize<'de>>(bytes: &[u8]) -> core::result::Result<T, Error> {
+            T::deserialize(SeqDeserializer::<_, Error>::new(bytes.iter().copied()))
+        }
+
+        let (pk, sk) = keygen_from_prf_output::<KEM_512>(&[3u8; 64]);
+        assert_eq!(from_bytes::<EncapsKey<KEM_512>>(&pk.ek).unwrap(), pk.ek);
+        assert_eq!(from_bytes::<DecapsKey<KEM_512>>(&sk.dk).unwrap(), sk.dk);
+
+        assert!(from_bytes::<EncapsKey<KEM_768>>(&pk.ek).is_err());
+        assert!(from_bytes::<EncapsKey<KEM_512>>(&pk.ek[1..]).is_err());
+        assert!(from_bytes::<DecapsKey<KEM_768>>(&sk.dk).is_err());
+        assert!(from_bytes::<DecapsKey<KEM_512>>(&Vec::new()).is_err());
+    }
+
+    #[test]
+    fn keygen_with_context_separates_contexts() {
+        use super::ml_kem_keygen_with_context;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let keygen = |context: &[u8]| {
+            let mut rng = StdRng::seed_from_u64(1178);
+            ml_kem_keygen_with_context::<KEM_768, _>(&mut rng, context).unwrap()
+        };
+        let (pk_a, sk_a) = keygen(b"tenant-a");
+        let (pk_b, sk_b) = keygen(b"tenant-b");
+        assert_ne!(pk_a.ek, pk_b.ek);
+        assert_ne!(sk_a.dk, sk_b.dk);
+
+        let (pk_a2, _) = keygen(b"tenant-a");
+        assert_eq!(pk_a.ek, pk_a2.ek);
+    }
+
+    #[test]
+    fn keygen_batch_matches_sequential_keygen() {
+        use super::ml_kem_keygen;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(1161);
+        let batch = mlkem_keygen_batch::<KEM_768, _>(3, &mut rng).unwrap();
+        let mut rng = StdRng::seed_from_u64(1161);
+        for (pk, sk) in batch.iter() {
+            let (ek, dk) = ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap();
+            assert_eq!(pk.ek, ek.ek);
+            assert_eq!(sk.dk, dk.dk);
+        }
+        assert_ne!(batch[0].0.ek, batch[1].0.ek);
+        assert!(mlkem_keygen_batch::<KEM_768, _>(0, &mut rng)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn keygen_from_prf_output_splits_d_and_z() {
+        let mut g = [0u8; 64];
+        g[32..].copy_from_slice(&[7u8; 32]);
+        let (pk, sk) = keygen_from_prf_output::<KEM_768>(&g);
+
+        let (ek, _) = k_pke_keygen::<KEM_768>(&[0u8; 32]);
+        assert_eq!(pk.ek[..], ek[..]);
+        assert_eq!(sk.dk[sk.dk.len() - 32..], [7u8; 32]);
+        assert!(sk.ek_hash_matches());
+    }
+
+    struct Fixed(u8);
+
+    impl EntropySource for Fixed {
+        fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+            dest.fill(self.0);
+            Ok(())
+        }
+    }
+
+    struct Broken;
+
+    impl EntropySource for Broken {
+        fn fill_bytes(&mut self, _: &mut [u8]) -> Result<()> {
+            Err(KemError::EntropyFailure)
+        }
+    }
+
+    struct Counting;
+
+    impl EntropySource for Counting {
+        fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+            dest.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn keygen_from_entropy_source() {
+        let (pk, sk) = ml_kem_keygen_from_entropy::<KEM_768, _>(&mut Fixed(9)).unwrap();
+        let (ek, _) = k_pke_keygen::<KEM_768>(&[9u8; 32]);
+        assert_eq!(pk.ek[..], ek[..]);
+        assert!(sk.ek_hash_matches());
+
+        // d and z are separate draws, not one seed used twice
+        let (pk, sk) = ml_kem_keygen_from_entropy::<KEM_768, _>(&mut Counting).unwrap();
+        let seeds: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let (ek, _) = k_pke_keygen::<KEM_768>(&seeds[..32]);
+        assert_eq!(pk.ek[..], ek[..]);
+        assert_eq!(sk.dk[sk.dk.len() - 32..], seeds[32..]);
+
+        assert_eq!(
+            ml_kem_keygen_from_entropy::<KEM_768, _>(&mut Broken).unwrap_err(),
+            KemError::EntropyFailure
+        );
+    }
+
+    #[test]
+    fn compressed_public_key_roundtrip() {
+        let (pk, _) = keygen_from_prf_output::<KEM_768>(&[3u8; 64]);
+        let ek = pk.ek.clone();
+
+        let cpk = CompressedPublicKey::try_from(pk).unwrap();
+        assert_eq!(cpk.t_bytes(), &ek[..384 * 3]);
+        assert_eq!(cpk.rho()[..], ek[384 * 3..]);
+        assert_eq!(KEMPublicKey::from(cpk).ek, ek);
+
+        assert!(CompressedPublicKey::<KEM_768>::from_ek(&ek[1..]).is_err());
+    }
+
+    #[test]
+    fn pack_unpack_ek_roundtrip() {
+        let (pk, _) = keygen_from_prf_output::<KEM_768>(&[4u8; 64]);
+        let (t_bytes, rho) = unpack_ek::<KEM_768>(&pk.ek);
+        assert_eq!(t_bytes.len(), 384 * 3);
+        assert_eq!(rho.len(), 32);
+        assert_eq!(pack_ek(t_bytes, rho), pk.ek[..]);
+    }
+
+    // Pins the K-PKE keygen output for a fixed seed so refactors of the
+    // linear algebra cannot silently change it. Values from OpenSSL 3.5
+    // ML-KEM-768 with d = 0^32.
+    #[test]
+    fn k_pke_keygen_is_stable() {
+        let (ek, dk) = k_pke_keygen::<KEM_768>(&[0u8; 32]);
+        assert_eq!(
+            Sha3_256::digest(&ek)[..8],
+            [0x07, 0xf8, 0x1a, 0x8b, 0x0e, 0x26, 0x6a, 0x3e]
+        );
+        assert_eq!(
+            Sha3_256::digest(&dk)[..8],
+            [0x33, 0x4a, 0x8b, 0x60, 0xcf, 0x2c, 0xbc, 0xe4]
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use crate::error::{KemError, Result};
+use core::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The 32-byte K-PKE plaintext `m`.
+///
+/// In decapsulation this is the recovered `m'`, which is as sensitive as the
+/// shared secret derived from it. The bytes are zeroized on drop and
+/// redacted from `Debug`.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Message([u8; 32]);
+
+impl Message {
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for Message {
+    fn from(m: [u8; 32]) -> Self {
+        Message(m)
+    }
+}
+
+impl TryFrom<&[u8]> for Message {
+    type Error = KemError;
+
+    fn try_from(m: &[u8]) -> Result<Self> {
+        let m: [u8; 32] = m.try_into().map_err(|_| KemError::InvalidInput)?;
+        Ok(Message(m))
+    }
+}
+
+impl AsRef<[u8]> for Message {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Message").field(&"<redacted>").finish()
+    }
+}
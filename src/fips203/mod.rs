@@ -1,3 +1,9 @@
 pub mod decrypt;
 pub mod encrypt;
+pub mod kem;
+pub mod key_agreement;
+mod key_format;
 pub mod keygen;
+pub mod message;
+pub mod primitives;
+pub mod shared_secret;
@@ -0,0 +1,120 @@
+//! Cryptographic functions from FIPS 203, Section 4.1.
+
+use crate::math::xof::{XofStream128, XofStream256};
+use alloc::{vec, vec::Vec};
+use sha3::{
+    digest::{Digest, ExtendableOutput, Update, XofReader},
+    Sha3_256, Sha3_512, Shake256,
+};
+
+/// `PRF_eta(s, b) = SHAKE-256(s || b, 8 * 64 * eta)`, returning `64 * eta`
+/// bytes.
+///
+/// FIPS 203 only uses `s` of 32 bytes and `eta` in `{2, 3}`, but neither is
+/// checked here.
+pub fn prf(s: &[u8], b: u8, eta: usize) -> Vec<u8> {
+    let mut out = vec![0u8; 64 * eta];
+    prf_into(s, b, &mut out);
+    out
+}
+
+/// [`prf`] writing `out.len()` bytes into `out` instead of allocating.
+pub fn prf_into(s: &[u8], b: u8, out: &mut [u8]) {
+    let mut stream = XofStream256::new();
+    stream.update(s);
+    stream.update(&[b]);
+    stream.read(out);
+}
+
+/// `XOF(rho, i, j) = SHAKE-128(rho || i || j)`, as a reader positioned at
+/// the start of the output stream.
+pub fn xof(rho: &[u8], i: u8, j: u8) -> impl XofReader {
+    let mut stream = XofStream128::new();
+    stream.update(rho);
+    stream.update(&[i, j]);
+    stream.finalize_xof()
+}
+
+/// `H(s) = SHA3-256(s)`.
+pub fn h(input: &[u8]) -> [u8; 32] {
+    Sha3_256::digest(input).into()
+}
+
+/// `G(c) = SHA3-512(c)`. Callers split the output into two 32-byte halves.
+pub fn g(input: &[u8]) -> [u8; 64] {
+    let mut out = [0u8; 64];
+    out.copy_from_slice(&Sha3_512::digest(input));
+    out
+}
+
+/// `J(z || c) = SHAKE-256(z || c, 8 * 32)`, the implicit rejection key.
+pub fn j(z: &[u8], c: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    let mut hasher = Shake256::default();
+    hasher.update(z);
+    hasher.update(c);
+    hasher.finalize_xof().read(&mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{g, h, j, prf, xof};
+    use sha3::digest::XofReader;
+
+    fn hex(bytes: &[u8]) -> alloc::string::String {
+        bytes.iter().map(|b| alloc::format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    #[cfg_attr(feature = "blake3-xof", ignore = "pinned to SHAKE output")]
+    fn prf_matches_shake256() {
+        // SHAKE-256(0^32 || 0x00), first 32 bytes
+        let out = prf(&[0u8; 32], 0, 2);
+        assert_eq!(out.len(), 128);
+        assert_eq!(
+            hex(&out[..32]),
+            "c03fcc81e73609875b3b98cb941c7806585af7ce3676be1ac5f5ef96dcd52c5a"
+        );
+
+        // SHAKE-256(0x00..0x1f || 0x01), last 16 of 192 bytes
+        let s: [u8; 32] = core::array::from_fn(|i| i as u8);
+        let out = prf(&s, 1, 3);
+        assert_eq!(out.len(), 192);
+        assert_eq!(hex(&out[176..]), "22a385937ee7abf713407f949d9deefd");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "blake3-xof", ignore = "pinned to SHAKE output")]
+    fn xof_matches_shake128() {
+        // SHAKE-128(0^32 || 0x01 || 0x02), first 32 bytes
+        let mut out = [0u8; 32];
+        xof(&[0u8; 32], 1, 2).read(&mut out);
+        assert_eq!(
+            hex(&out),
+            "8a40e60709b88a0880a5e6fbe481d56710b612ce28d937b2da9648775b7bc4d5"
+        );
+    }
+
+    #[test]
+    fn g_and_h_match_sha3() {
+        // SHA3-512("") and SHA3-256("")
+        assert_eq!(
+            hex(&g(&[])[..32]),
+            "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6"
+        );
+        assert_eq!(
+            hex(&h(&[])),
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"
+        );
+    }
+
+    #[test]
+    fn j_matches_shake256() {
+        // SHAKE-256(0^32 || 0x01), first 32 bytes
+        assert_eq!(
+            hex(&j(&[0u8; 32], &[1])),
+            "d3593e6fc40e08fc4ca6cf6b52a09e576b527af2d50e9b63e6bdbbad3ef37b91"
+        );
+    }
+}
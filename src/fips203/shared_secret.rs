@@ -0,0 +1,54 @@
+use crate::error::{KemError, Result};
+use core::fmt;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The 32-byte shared secret `K` agreed on by encapsulation and
+/// decapsulation.
+///
+/// ## Security
+///
+/// The bytes are zeroized on drop and redacted from `Debug`. Compare values
+/// with [`ConstantTimeEq::ct_eq`]; there is deliberately no `PartialEq`.
+#[must_use]
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SharedSecret([u8; 32]);
+
+impl SharedSecret {
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for SharedSecret {
+    fn from(k: [u8; 32]) -> Self {
+        SharedSecret(k)
+    }
+}
+
+impl TryFrom<&[u8]> for SharedSecret {
+    type Error = KemError;
+
+    fn try_from(k: &[u8]) -> Result<Self> {
+        let k: [u8; 32] = k.try_into().map_err(|_| KemError::InvalidInput)?;
+        Ok(SharedSecret(k))
+    }
+}
+
+impl AsRef<[u8]> for SharedSecret {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl ConstantTimeEq for SharedSecret {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl fmt::Debug for SharedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedSecret").field(&"<redacted>").finish()
+    }
+}
@@ -1,5 +1,7 @@
 #![no_std]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod error;
 
@@ -11,4 +13,5 @@ pub mod fips203;
 pub mod math;
 
 // Re-export commonly used types
+pub use constants::parameter_sets::{ciphertext_size, private_key_size, public_key_size};
 pub use error::{KemError, Result};
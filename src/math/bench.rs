@@ -0,0 +1,32 @@
+//! Public handles on crate-private arithmetic for the criterion harness in
+//! `benches/`. Only built with the `testing` feature and not part of the
+//! API.
+
+use super::{field_element::FieldElement as F, ntt_element::NttElement};
+use rand_core::{CryptoRng, RngCore};
+
+/// An opaque `NttElement`.
+#[derive(Clone, Copy)]
+pub struct Ntt(NttElement);
+
+impl Ntt {
+    pub fn from_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Ntt(NttElement::from_rng(rng))
+    }
+
+    pub fn hadamard_product(self, other: Self) -> Self {
+        Ntt(self.0.hadamard_product(other.0))
+    }
+
+    pub fn add_scaled(&mut self, scalar: u16, other: &Self) {
+        self.0.add_scaled(F::new(scalar), &other.0);
+    }
+
+    /// The two-pass form `add_scaled` replaces.
+    pub fn scale_then_add(&mut self, scalar: u16, other: &Self) {
+        let s = F::new(scalar);
+        let mut x = other.0;
+        x.apply_fn(|c| c * s);
+        self.0 += x;
+    }
+}
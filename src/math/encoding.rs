@@ -1,6 +1,5 @@
 use super::{field_element::FieldElement, ntt_element::NttElement, ring_element::RingElement};
 use crate::constants::ml_kem_constants::q;
-use alloc::vec::Vec;
 use core::{
     fmt::Debug,
     ops::{Div, Mul},
@@ -39,6 +38,7 @@ where
 
 type Integer = u16;
 
+#[must_use]
 pub fn byte_decode<D: EncodingSize>(bytes: &[u8]) -> RingElement {
     let val_step = D::ValueStep::USIZE;
     let byte_step = D::ByteStep::USIZE;
@@ -69,35 +69,35 @@ pub fn byte_decode<D: EncodingSize>(bytes: &[u8]) -> RingElement {
     vals
 }
 
-pub fn byte_encode<D: EncodingSize>(vals: &[FieldElement; 256]) -> Vec<u8> {
+/// `ByteEncode_D` into `out`, which must be `32 * D` bytes long.
+pub fn byte_encode_into<D: EncodingSize>(vals: &[FieldElement; 256], out: &mut [u8]) {
     let val_step = D::ValueStep::USIZE;
     let byte_step = D::ByteStep::USIZE;
+    assert_eq!(out.len(), D::EncodedPolynomialSize::USIZE);
 
-    let mut bytes = Vec::with_capacity(D::USIZE);
-
-    for v in vals.chunks(val_step) {
+    for (v, bytes) in vals.chunks(val_step).zip(out.chunks_exact_mut(byte_step)) {
         let mut x = 0u128;
         for (j, vj) in v.iter().enumerate() {
             x |= u128::from(vj.val()) << (D::USIZE * j);
         }
 
         let xb = x.to_le_bytes();
-        bytes.extend_from_slice(&xb[..byte_step]);
+        bytes.copy_from_slice(&xb[..byte_step]);
     }
-    bytes
 }
 
 pub trait Encode<D: EncodingSize> {
     type EncodedSize: ArraySize;
-    fn encode(&self) -> Vec<u8>;
+    /// Encodes into `out`, which must be `EncodedSize` bytes long.
+    fn encode_into(&self, out: &mut [u8]);
     fn decode(enc: &[u8]) -> Self;
 }
 
 impl<D: EncodingSize> Encode<D> for RingElement {
     type EncodedSize = D::EncodedPolynomialSize;
 
-    fn encode(&self) -> Vec<u8> {
-        byte_encode::<D>(&self.coefs)
+    fn encode_into(&self, out: &mut [u8]) {
+        byte_encode_into::<D>(&self.coefs, out);
     }
 
     fn decode(enc: &[u8]) -> Self {
@@ -108,8 +108,8 @@ impl<D: EncodingSize> Encode<D> for RingElement {
 impl<D: EncodingSize> Encode<D> for NttElement {
     type EncodedSize = D::EncodedPolynomialSize;
 
-    fn encode(&self) -> Vec<u8> {
-        byte_encode::<D>(&self.coefs)
+    fn encode_into(&self, out: &mut [u8]) {
+        byte_encode_into::<D>(&self.coefs, out);
     }
 
     fn decode(enc: &[u8]) -> Self {
@@ -139,18 +139,12 @@ pub trait Compress {
 
 impl Compress for RingElement {
     fn compress<D: CompressionFactor>(&mut self) -> &Self {
-        for x in &mut self.coefs {
-            x.compress::<D>();
-        }
-
+        self.map_coefficients_inplace(|mut x| *Compress::compress::<D>(&mut x));
         self
     }
 
     fn decompress<D: CompressionFactor>(&mut self) -> &Self {
-        for x in &mut self.coefs {
-            x.decompress::<D>();
-        }
-
+        self.map_coefficients_inplace(|mut x| *Compress::decompress::<D>(&mut x));
         self
     }
 }
@@ -1,19 +1,41 @@
-use super::encoding::{Compress, CompressionFactor};
+use super::{
+    encoding::{Compress, CompressionFactor},
+    util::Truncate,
+};
 use crate::constants::{
     barrett_constants::{MULTIPLIER as bar_mul, SHIFT as bar_shift},
     ml_kem_constants::q,
 };
-use core::ops::{Add, AddAssign, Mul, Neg, Sub};
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Add, AddAssign, Mul, Neg, Sub},
+};
+use subtle::{Choice, ConditionallySelectable};
+use zeroize::Zeroize;
 
 pub enum OperationError {
     UnreducedFieldElementError,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Zeroize)]
 /// An integer modulo Q
 pub struct FieldElement(pub u16);
 
+// The NTT stops one layer short, so the scaling factor is (n/2)^{-1}.
+const _: () = assert!(FieldElement::INV_N.0 as u32 * 128 % q as u32 == 1);
+
 impl FieldElement {
+    pub const ZERO: Self = FieldElement(0);
+    pub const ONE: Self = FieldElement(1);
+    /// q itself, which is congruent to zero.
+    pub const Q: Self = FieldElement(0);
+    /// The scaling factor applied at the end of the inverse NTT.
+    ///
+    /// FIPS 203's NTT has only 7 layers (256 = 2 * 128), so this is the
+    /// inverse of 128 mod q rather than of n = 256.
+    pub const INV_N: Self = FieldElement(3303);
+
     pub fn new(val: u16) -> Self {
         let mut f = FieldElement(val);
         f.reduce_once();
@@ -40,6 +62,15 @@ impl FieldElement {
         self.0 = x;
     }
 
+    /// Applies [`FieldElement::reduce_once`] to every value in `vals`,
+    /// bringing each from `[0, 2q)` into `[0, q)`.
+    pub(crate) fn batch_reduce(vals: &mut [u16]) {
+        for v in vals.iter_mut() {
+            let x = v.wrapping_sub(q);
+            *v = x.wrapping_add((x >> 15).wrapping_mul(q));
+        }
+    }
+
     pub fn check_reduced(self) -> Result<Self, OperationError> {
         if self.val() > q {
             Err(OperationError::UnreducedFieldElementError)
@@ -52,6 +83,22 @@ impl FieldElement {
         self.0
     }
 
+    /// The centered representative of this element in `[-(q-1)/2, (q-1)/2]`.
+    pub fn centered(self) -> i16 {
+        if self.val() > q / 2 {
+            self.val() as i16 - q as i16
+        } else {
+            self.val() as i16
+        }
+    }
+
+    /// Returns `-self` if `negate` is set and `self` otherwise, without
+    /// branching on `negate`.
+    pub fn conditional_negate(self, negate: Choice) -> Self {
+        let v = u16::conditional_select(&self.val(), &(q - self.val()), negate);
+        FieldElement::new(v)
+    }
+
     // FIPS 203 (DRAFT), Definition 4.5.
     // TODO: sometimes these might need to be called with
     // values of du/dv that are different from param defs
@@ -81,7 +128,7 @@ impl FieldElement {
         Self::from(quotient as u16)
     }
 
-    fn barrett_reduce(product: u32) -> Self {
+    pub(crate) fn barrett_reduce(product: u32) -> Self {
         let quotient: u32 = ((u64::from(product) * u64::from(bar_mul)) >> bar_shift) as u32;
         Self::new((product - quotient * u32::from(q)) as u16)
     }
@@ -104,8 +151,7 @@ impl Compress for FieldElement {
         if remainder > (q + q / 2).into() {
             adjusted_quotient = adjusted_quotient.wrapping_add(1);
         }
-        let mask = (1u64 << D::USIZE) - 1;
-        self.0 = (adjusted_quotient & mask) as u16;
+        self.0 = (adjusted_quotient & u64::from(D::MASK)) as u16;
 
         self
     }
@@ -114,13 +160,36 @@ impl Compress for FieldElement {
     fn decompress<D: CompressionFactor>(&mut self) -> &FieldElement {
         let dividend = u32::from(self.val());
         let dividend = dividend.wrapping_mul(q.into());
-        let mut quotient = dividend.wrapping_shr(D::USIZE as u32);
-        quotient = quotient.wrapping_add((dividend.wrapping_shr(D::USIZE as u32 - 1)) & 1);
+        let quotient = dividend
+            .wrapping_add(D::POW2_HALF)
+            .wrapping_shr(D::USIZE as u32);
         self.0 = quotient as u16;
         self
     }
 }
 
+/// Hashes the stored `u16`, which is the canonical representative for any
+/// element built through [`FieldElement::new`].
+impl Hash for FieldElement {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.val().hash(state);
+    }
+}
+
+/// Zero-padded four digit hex, e.g. `0cf1` for 3313.
+impl fmt::LowerHex for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04x}", self.0)
+    }
+}
+
+/// Zero-padded four digit hex, e.g. `0CF1` for 3313.
+impl fmt::UpperHex for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04X}", self.0)
+    }
+}
+
 impl AddAssign for FieldElement {
     fn add_assign(&mut self, other: Self) {
         self.0 = (self.val() + other.val()) % q;
@@ -141,7 +210,7 @@ impl Mul<u16> for FieldElement {
 
     fn mul(self, other: u16) -> Self {
         let product = u32::from(self.val()) * u32::from(other);
-        Self::barrett_reduce(product)
+        product.truncate()
     }
 }
 
@@ -150,7 +219,7 @@ impl Mul<FieldElement> for u16 {
 
     fn mul(self, other: FieldElement) -> Self {
         let product = u32::from(other.val()) * u32::from(self);
-        FieldElement::barrett_reduce(product).val()
+        Truncate::<FieldElement>::truncate(product).val()
     }
 }
 
@@ -159,7 +228,7 @@ impl Mul<FieldElement> for FieldElement {
 
     fn mul(self, other: FieldElement) -> Self {
         let product = u32::from(other.val()) * u32::from(self.val());
-        FieldElement::barrett_reduce(product)
+        product.truncate()
     }
 }
 
@@ -257,6 +326,26 @@ mod tests {
         }
     }
 
+    // Checks the Barrett constants (MULTIPLIER = 5039, SHIFT = 24) against
+    // naive reduction for every product of two reduced elements. About
+    // 11 million pairs: well under a second with the opt-level 3 test
+    // profile, tens of seconds in an unoptimized build. Run with
+    // `RUSTFLAGS="--cfg slow_tests" cargo test mod_reduction_test`.
+    #[cfg(slow_tests)]
+    #[test]
+    fn mod_reduction_test() {
+        for a in 0..q {
+            for b in 0..q {
+                let expected = (u32::from(a) * u32::from(b)) % u32::from(q);
+                assert_eq!(
+                    u32::from((F::new(a) * F::new(b)).val()),
+                    expected,
+                    "Failed at a = {a} and b = {b}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_multiplication_with_potential_overflow() {
         let a = F::new(3000);
@@ -270,6 +359,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn batch_reduce_matches_reduce_once() {
+        let mut vals: [u16; 2 * q as usize] = core::array::from_fn(|i| i as u16);
+        F::batch_reduce(&mut vals);
+        for (i, &v) in vals.iter().enumerate() {
+            assert_eq!(v, F::new(i as u16).val(), "Failed at {i}");
+        }
+    }
+
+    #[test]
+    fn conditional_negate_selects() {
+        use subtle::Choice;
+
+        for v in [0, 1, 2, q / 2, q - 1] {
+            let a = F::new(v);
+            assert_eq!(a.conditional_negate(Choice::from(0)), a);
+            assert_eq!(a.conditional_negate(Choice::from(1)), -a);
+            assert_eq!((a.conditional_negate(Choice::from(1)) + a).val(), 0);
+        }
+    }
+
     #[test]
     fn test_check_reduced_ok() {
         assert!(F::new(q - 1).check_reduced().is_ok());
@@ -315,6 +425,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn exhaustive_test_centered() {
+        for i in 0..q {
+            let c = F::new(i).centered();
+            assert!(c.unsigned_abs() <= (q - 1) / 2);
+            assert_eq!((i32::from(c)).rem_euclid(q.into()), i.into());
+        }
+    }
+
+    #[test]
+    fn test_hex_formatting() {
+        use alloc::format;
+        assert_eq!(format!("{:x}", F::new(0x0CF1)), "0cf1");
+        assert_eq!(format!("{:X}", F::new(0x0CF1)), "0CF1");
+        assert_eq!(format!("{:x}", F::new(1)), "0001");
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(F::ZERO, F::new(0));
+        assert_eq!(F::ONE, F::new(1));
+        assert_eq!(F::Q, F::new(q));
+        assert_eq!((F::INV_N * 128).val(), 1);
+    }
+
     #[test]
     fn test_compress_with_mask() {
         let fe = F(12345);
@@ -324,4 +459,26 @@ mod tests {
             "Compressed value should be within the mask limit"
         );
     }
+
+    #[test]
+    fn field_element_as_hash_key() {
+        extern crate std;
+        use core::hash::{Hash, Hasher};
+        use std::collections::{hash_map::DefaultHasher, HashMap};
+
+        let hash = |f: F| {
+            let mut h = DefaultHasher::new();
+            f.hash(&mut h);
+            h.finish()
+        };
+        assert_eq!(hash(F::new(3329)), hash(F::new(0)));
+
+        let mut counts = HashMap::new();
+        for v in [0, 1, 3329, 3330, 7] {
+            *counts.entry(F::new(v)).or_insert(0) += 1;
+        }
+        assert_eq!(counts[&F::new(0)], 2);
+        assert_eq!(counts[&F::new(1)], 2);
+        assert_eq!(counts[&F::new(7)], 1);
+    }
 }
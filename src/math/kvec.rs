@@ -0,0 +1,169 @@
+use super::ntt_element::NttElement;
+use crate::constants::parameter_sets::ParameterSet;
+use core::{
+    fmt,
+    ops::{Add, AddAssign, Index, IndexMut, Mul},
+    slice::{Iter, IterMut},
+};
+use hybrid_array::Array;
+
+/// A length-k vector of polynomials in the NTT domain, where k is taken
+/// from the parameter set.
+pub struct KVec<P: ParameterSet> {
+    inner: Array<NttElement, P::K>,
+}
+
+impl<P: ParameterSet> KVec<P> {
+    pub fn iter(&self) -> Iter<'_, NttElement> {
+        self.inner.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, NttElement> {
+        self.inner.iter_mut()
+    }
+
+    /// Inner product of two vectors, computed entirely in the NTT domain.
+    pub fn dot(&self, other: &KVec<P>) -> NttElement {
+        self.iter().zip(other.iter()).map(|(a, b)| *a * *b).sum()
+    }
+}
+
+impl<P: ParameterSet> Default for KVec<P> {
+    fn default() -> Self {
+        KVec {
+            inner: Array::default(),
+        }
+    }
+}
+
+impl<P: ParameterSet> Clone for KVec<P> {
+    fn clone(&self) -> Self {
+        KVec {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<P: ParameterSet> Index<usize> for KVec<P> {
+    type Output = NttElement;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        &self.inner[i]
+    }
+}
+
+impl<P: ParameterSet> IndexMut<usize> for KVec<P> {
+    fn index_mut(&mut self, i: usize) -> &mut Self::Output {
+        &mut self.inner[i]
+    }
+}
+
+impl<P: ParameterSet> AddAssign for KVec<P> {
+    fn add_assign(&mut self, other: Self) {
+        for (lhs, rhs) in self.iter_mut().zip(other.iter()) {
+            *lhs += *rhs;
+        }
+    }
+}
+
+impl<P: ParameterSet> Add for KVec<P> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self::Output {
+        self += other;
+        self
+    }
+}
+
+/// Scales every entry by `rhs` (pointwise product in the NTT domain).
+impl<P: ParameterSet> Mul<NttElement> for KVec<P> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: NttElement) -> Self::Output {
+        for x in self.iter_mut() {
+            *x = *x * rhs;
+        }
+        self
+    }
+}
+
+/// Collects exactly k elements into a vector.
+///
+/// # Panics
+///
+/// If the iterator yields fewer than k elements. Extra elements are ignored.
+impl<P: ParameterSet> FromIterator<NttElement> for KVec<P> {
+    fn from_iter<I: IntoIterator<Item = NttElement>>(iter: I) -> Self {
+        let mut out = KVec::default();
+        let mut iter = iter.into_iter();
+        for x in out.iter_mut() {
+            *x = iter.next().expect("iterator shorter than k");
+        }
+        out
+    }
+}
+
+/// `&a * &b` is the inner product, see [`KVec::dot`].
+impl<P: ParameterSet> Mul<&KVec<P>> for &KVec<P> {
+    type Output = NttElement;
+
+    fn mul(self, rhs: &KVec<P>) -> Self::Output {
+        self.dot(rhs)
+    }
+}
+
+impl<P: ParameterSet> fmt::Debug for KVec<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KVec;
+    use crate::{constants::parameter_sets::KEM_768, math::ntt_element::NttElement};
+
+    #[test]
+    fn dot_matches_loop() {
+        let mut a = KVec::<KEM_768>::default();
+        let mut b = KVec::<KEM_768>::default();
+        for i in 0..3 {
+            a[i] = NttElement::sample_ntt(&[1u8; 32], i, 0);
+            b[i] = NttElement::sample_ntt(&[2u8; 32], i, 1);
+        }
+
+        let mut expected = NttElement::zero();
+        for i in 0..3 {
+            expected += a[i] * b[i];
+        }
+        assert_eq!(a.dot(&b).coefs, expected.coefs);
+        assert_eq!((&a * &b).coefs, expected.coefs);
+    }
+
+    #[test]
+    fn scale_by_ntt_element() {
+        let mut a = KVec::<KEM_768>::default();
+        for i in 0..3 {
+            a[i] = NttElement::sample_ntt(&[6u8; 32], i, 2);
+        }
+        let c = NttElement::sample_ntt(&[7u8; 32], 0, 0);
+        let scaled = a.clone() * c;
+        for i in 0..3 {
+            assert_eq!(scaled[i].coefs, (a[i] * c).coefs);
+        }
+    }
+
+    #[test]
+    fn add_zero_is_identity() {
+        let mut a = KVec::<KEM_768>::default();
+        for i in 0..3 {
+            a[i] = NttElement::sample_ntt(&[3u8; 32], i, i);
+        }
+        let zero = KVec::<KEM_768>::default();
+        assert!(zero.iter().all(NttElement::is_zero));
+        let sum = a.clone() + zero;
+        for i in 0..3 {
+            assert_eq!(sum[i].coefs, a[i].coefs);
+        }
+    }
+}
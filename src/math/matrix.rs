@@ -0,0 +1,282 @@
+use super::{kvec::KVec, ntt_element::NttElement};
+use crate::constants::parameter_sets::ParameterSet;
+use core::ops::{Index, IndexMut, Mul};
+use hybrid_array::{typenum::Unsigned, Array};
+
+/// A k×k matrix of polynomials in the NTT domain, stored row-major.
+pub struct AHatMatrix<P: ParameterSet> {
+    inner: Array<NttElement, P::KSquared>,
+}
+
+impl<P: ParameterSet> Default for AHatMatrix<P> {
+    fn default() -> Self {
+        AHatMatrix {
+            inner: Array::default(),
+        }
+    }
+}
+
+impl<P: ParameterSet> Clone for AHatMatrix<P> {
+    fn clone(&self) -> Self {
+        AHatMatrix {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// Indexed by `(row, column)`.
+impl<P: ParameterSet> Index<(usize, usize)> for AHatMatrix<P> {
+    type Output = NttElement;
+
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        &self.inner[i * P::K::USIZE + j]
+    }
+}
+
+impl<P: ParameterSet> IndexMut<(usize, usize)> for AHatMatrix<P> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Self::Output {
+        &mut self.inner[i * P::K::USIZE + j]
+    }
+}
+
+impl<P: ParameterSet> AHatMatrix<P> {
+    /// `A_hat` as generated in K-PKE key generation: entry `(i, j)` is
+    /// `SampleNTT(rho || j || i)`.
+    ///
+    /// Encryption uses the transpose, see [`AHatMatrix::transpose`].
+    pub fn from_rho(rho: &[u8]) -> Self {
+        let k = P::K::USIZE;
+        // `rho` is absorbed once and the context cloned per entry, see
+        // `NttElement::sample_ntt_init_rho`.
+        let ctx = NttElement::sample_ntt_init_rho(rho);
+        let mut a_hat = Self::default();
+        for i in 0..k {
+            for j in 0..k {
+                a_hat[(i, j)] = NttElement::sample_ntt_from_ctx(ctx.clone(), j, i);
+            }
+        }
+        a_hat
+    }
+
+    pub fn transpose(&self) -> Self {
+        let k = P::K::USIZE;
+        let mut out = Self::default();
+        for i in 0..k {
+            for j in 0..k {
+                out[(i, j)] = self[(j, i)];
+            }
+        }
+        out
+    }
+
+    /// A copy of row `i`.
+    pub fn row(&self, i: usize) -> KVec<P> {
+        let k = P::K::USIZE;
+        self.inner[i * k..(i + 1) * k].iter().copied().collect()
+    }
+
+    /// Iterates over the k rows.
+    pub fn rows(&self) -> Rows<'_, P> {
+        Rows {
+            matrix: self,
+            next: 0,
+        }
+    }
+}
+
+/// Iterator over the rows of a borrowed [`AHatMatrix`], see
+/// [`AHatMatrix::rows`].
+pub struct Rows<'a, P: ParameterSet> {
+    matrix: &'a AHatMatrix<P>,
+    next: usize,
+}
+
+impl<P: ParameterSet> Iterator for Rows<'_, P> {
+    type Item = KVec<P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == P::K::USIZE {
+            return None;
+        }
+        self.next += 1;
+        Some(self.matrix.row(self.next - 1))
+    }
+}
+
+/// Iterator over the rows of an owned [`AHatMatrix`].
+pub struct IntoRows<P: ParameterSet> {
+    matrix: AHatMatrix<P>,
+    next: usize,
+}
+
+impl<P: ParameterSet> Iterator for IntoRows<P> {
+    type Item = KVec<P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == P::K::USIZE {
+            return None;
+        }
+        self.next += 1;
+        Some(self.matrix.row(self.next - 1))
+    }
+}
+
+impl<P: ParameterSet> IntoIterator for AHatMatrix<P> {
+    type Item = KVec<P>;
+    type IntoIter = IntoRows<P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoRows {
+            matrix: self,
+            next: 0,
+        }
+    }
+}
+
+impl<'a, P: ParameterSet> IntoIterator for &'a AHatMatrix<P> {
+    type Item = KVec<P>;
+    type IntoIter = Rows<'a, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows()
+    }
+}
+
+/// Computes the matrix-vector product `A ∘ s` in the NTT domain.
+pub fn ntt_matrix_mul<P: ParameterSet>(a: &AHatMatrix<P>, s: &KVec<P>) -> KVec<P> {
+    let k = P::K::USIZE;
+    let mut out = KVec::<P>::default();
+    for i in 0..k {
+        out[i] = (0..k).map(|j| a[(i, j)] * s[j]).sum();
+    }
+    out
+}
+
+/// `&a_hat * &s_hat` is the matrix-vector product, see [`ntt_matrix_mul`].
+impl<P: ParameterSet> Mul<&KVec<P>> for &AHatMatrix<P> {
+    type Output = KVec<P>;
+
+    fn mul(self, rhs: &KVec<P>) -> Self::Output {
+        ntt_matrix_mul(self, rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ntt_matrix_mul, AHatMatrix};
+    use crate::{
+        constants::parameter_sets::{ParameterSet, KEM_1024},
+        math::{kvec::KVec, ntt_element::NttElement},
+    };
+    use typenum::{U10, U12, U2, U3, U4};
+
+    #[test]
+    fn matrix_mul_matches_rowwise_dot() {
+        let mut a = AHatMatrix::<KEM_1024>::default();
+        let mut s = KVec::<KEM_1024>::default();
+        let mut rows = [
+            KVec::<KEM_1024>::default(),
+            KVec::default(),
+            KVec::default(),
+            KVec::default(),
+        ];
+        for i in 0..4 {
+            s[i] = NttElement::sample_ntt(&[1u8; 32], i, 9);
+            for j in 0..4 {
+                a[(i, j)] = NttElement::sample_ntt(&[2u8; 32], i, j);
+                rows[i][j] = a[(i, j)];
+            }
+        }
+
+        let product = ntt_matrix_mul(&a, &s);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(product[i].coefs, row.dot(&s).coefs);
+        }
+
+        let product_op = &a * &s;
+        for i in 0..4 {
+            assert_eq!(product_op[i].coefs, product[i].coefs);
+        }
+    }
+
+    #[test]
+    fn from_rho_matches_sample_ntt() {
+        let rho = [6u8; 32];
+        let a = AHatMatrix::<KEM_1024>::from_rho(&rho);
+        let a_t = a.transpose();
+        for i in 0..4 {
+            for j in 0..4 {
+                let expected = NttElement::sample_ntt(&rho, j, i);
+                assert_eq!(a[(i, j)].coefs, expected.coefs);
+                assert_eq!(a_t[(j, i)].coefs, expected.coefs);
+            }
+        }
+    }
+
+    #[test]
+    fn sample_ntt_row_matches_from_rho() {
+        let rho = [8u8; 32];
+        let a = AHatMatrix::<KEM_1024>::from_rho(&rho);
+        for (i, row) in a.rows().enumerate() {
+            let sampled = NttElement::sample_ntt_row(&rho, i, 4);
+            assert_eq!(sampled.len(), 4);
+            for j in 0..4 {
+                assert_eq!(sampled[j].coefs, row[j].coefs);
+            }
+        }
+    }
+
+    #[test]
+    fn row_iteration_matches_matrix_mul() {
+        let mut a = AHatMatrix::<KEM_1024>::default();
+        let mut s = KVec::<KEM_1024>::default();
+        for i in 0..4 {
+            s[i] = NttElement::sample_ntt(&[3u8; 32], i, 7);
+            for j in 0..4 {
+                a[(i, j)] = NttElement::sample_ntt(&[4u8; 32], i, j);
+            }
+        }
+        let product = &a * &s;
+
+        let mut i = 0;
+        for row in &a {
+            assert_eq!(row.dot(&s).coefs, product[i].coefs);
+            i += 1;
+        }
+        assert_eq!(i, 4);
+
+        let by_rows: KVec<KEM_1024> = a.into_iter().map(|row| row.dot(&s)).collect();
+        for i in 0..4 {
+            assert_eq!(by_rows[i].coefs, product[i].coefs);
+        }
+    }
+
+    // A parameter set that is deliberately not `Clone`. The containers clone
+    // their `NttElement`s (which are `Copy`) and must not require `P: Clone`.
+    struct NoClone;
+    impl ParameterSet for NoClone {
+        type K = U2;
+        type KSquared = U4;
+        type EtaOne = U3;
+        type EtaTwo = U2;
+        type Du = U10;
+        type Dv = U4;
+        type Encode12 = U12;
+        const LABEL: &'static str = "no-clone";
+        const NIST_SECURITY_CATEGORY: u8 = 1;
+        const DU_USIZE: usize = 10;
+        const DV_USIZE: usize = 4;
+    }
+
+    #[test]
+    fn clone_without_clone_parameter_set() {
+        let mut a = AHatMatrix::<NoClone>::default();
+        let mut s = KVec::<NoClone>::default();
+        a[(1, 0)] = NttElement::sample_ntt(&[5u8; 32], 1, 0);
+        s[1] = a[(1, 0)];
+
+        let (a2, s2) = (a.clone(), s.clone());
+        assert_eq!(a2[(1, 0)].coefs, a[(1, 0)].coefs);
+        assert_eq!(s2[1].coefs, s[1].coefs);
+    }
+}
@@ -1,5 +1,11 @@
+#[cfg(feature = "testing")]
+#[doc(hidden)]
+pub mod bench;
 pub(crate) mod encoding;
 pub(crate) mod field_element;
+pub(crate) mod kvec;
+pub(crate) mod matrix;
 pub(crate) mod ntt_element;
 pub(crate) mod ring_element;
-mod util;
+pub(crate) mod util;
+pub(crate) mod xof;
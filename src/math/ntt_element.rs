@@ -1,29 +1,46 @@
-use super::{field_element::FieldElement as F, ring_element::RingElement};
+use super::{field_element::FieldElement as F, ring_element::RingElement, xof::XofStream128};
 use crate::{
     constants::{
         ml_kem_constants::{n, q, ENCODE_12, MASK_12},
         K_MOD_ROOTS, K_NTT_ROOTS,
     },
     error::{KemError, Result},
+    fips203::primitives::xof,
 };
 use alloc::vec::Vec;
 use core::{
     fmt,
-    ops::{Add, AddAssign, Mul},
-};
-use sha3::{
-    digest::{ExtendableOutput, Update, XofReader},
-    Shake128,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, SubAssign},
 };
+#[cfg(any(test, feature = "testing"))]
+use rand_core::{CryptoRng, RngCore};
+use sha3::digest::XofReader;
+use subtle::{Choice, ConstantTimeEq};
 
 // TODO: define addition on NTT domain to save a transform?
 // or make addition generic for rings.
 
+/// An element of `T_q`, the NTT representation of a polynomial in `R_q`.
+///
+/// `coefs` are not polynomial coefficients: they are 128 pairs
+/// `(f̂_2i, f̂_2i+1)`, each the residue of the polynomial modulo
+/// `X² - ζ^(2·BitRev7(i) + 1)`. Multiplication in this basis is
+/// [`NttElement::hadamard_product`]. Use [`NttElement::from_ring_element`]
+/// to transform a polynomial and [`NttElement::from_ntt_coefficients`] to
+/// wrap values that are already in this basis, e.g. from `ByteDecode_12`.
+// `Clone` is derived on a `Copy` type, so `clone` and the default
+// `clone_from` both compile to a plain 512-byte copy. If non-`Copy` fields
+// are ever added, write `clone_from` out to reuse the existing storage.
 #[derive(Clone, Copy)]
 pub struct NttElement {
     pub coefs: [F; n],
 }
 
+/// The default `NttElement` is the additive identity (all coefficients zero).
+///
+/// `Array::<NttElement, _>::default()` in keygen and encrypt relies on this,
+/// so it must never be changed to an uninitialized or sampled state.
 impl Default for NttElement {
     fn default() -> Self {
         Self::zero()
@@ -31,26 +48,201 @@ impl Default for NttElement {
 }
 
 impl NttElement {
-    fn new(r: &mut RingElement) -> Self {
+    /// Applies the forward NTT to `r`.
+    pub fn from_ring_element(r: RingElement) -> Self {
         let mut ntt_el = NttElement { coefs: r.coefs };
         ntt_el.ntt();
         ntt_el
     }
 
+    /// Wraps coefficients that are already in the NTT basis, skipping the
+    /// forward transform.
+    pub fn from_ntt_coefficients(coefs: [F; n]) -> Self {
+        NttElement { coefs }
+    }
+
     pub fn zero() -> Self {
         NttElement {
             coefs: [F::zero(); n],
         }
     }
 
+    /// Builds an element from raw coefficients in `[0, 2q)`, reducing them
+    /// into `[0, q)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KemError::EncodingError`] if any value is `>= 2q`.
+    pub fn from_u16_array(mut vals: [u16; n]) -> Result<Self> {
+        if vals.iter().any(|&v| v >= 2 * q) {
+            return Err(KemError::EncodingError);
+        }
+        F::batch_reduce(&mut vals);
+        Ok(NttElement {
+            coefs: vals.map(F::from),
+        })
+    }
+
+    /// Brings every coefficient from `[0, 2q)` into `[0, q)`.
+    pub fn normalize(&mut self) {
+        for c in self.coefs.iter_mut() {
+            c.reduce_once();
+        }
+    }
+
+    /// The raw coefficient values.
+    pub fn to_u16_array(self) -> [u16; n] {
+        self.coefs.map(F::val)
+    }
+
+    /// Replaces every coefficient `c` with `f(c)`.
+    pub fn apply_fn(&mut self, f: impl Fn(F) -> F) {
+        for c in self.coefs.iter_mut() {
+            *c = f(*c);
+        }
+    }
+
+    /// `self += scalar * other`, in a single pass.
+    ///
+    /// Each coefficient computes `lhs + scalar * rhs` as one `u32` and
+    /// Barrett reduces it once; the sum is below `q²`, which is in range.
+    pub fn add_scaled(&mut self, scalar: F, other: &NttElement) {
+        for (lhs, rhs) in self.coefs.iter_mut().zip(other.coefs.iter()) {
+            let acc = u32::from(lhs.val()) + u32::from(scalar.val()) * u32::from(rhs.val());
+            *lhs = F::barrett_reduce(acc);
+        }
+    }
+
+    /// Returns true iff every coefficient is zero.
+    pub fn is_zero(&self) -> bool {
+        self.coefs.iter().all(|c| c.val() == 0)
+    }
+
+    /// Constant-time variant of [`NttElement::is_zero`].
+    pub fn ct_is_zero(&self) -> Choice {
+        self.coefs
+            .iter()
+            .fold(0u16, |acc, c| acc | c.val())
+            .ct_eq(&0)
+    }
+
+    /// `SampleNTT(XOF(ρ, i, j))`, with `i` and `j` absorbed as single bytes.
     pub fn sample_ntt(rho: &[u8], ii: usize, jj: usize) -> NttElement {
-        let mut hasher = Shake128::default();
-        hasher.update(rho);
-        hasher.update(ii.to_le_bytes().as_slice());
-        hasher.update(jj.to_le_bytes().as_slice());
+        debug_assert!(ii < 256 && jj < 256, "matrix index out of range");
+        Self::sample_ntt_from_xof(&mut xof(rho, ii as u8, jj as u8))
+    }
 
-        let mut reader = hasher.finalize_xof();
+    /// `SampleNTT` on a pre-built `ρ || i || j` input.
+    pub fn sample_ntt_from_bytes(input: &[u8; 34]) -> NttElement {
+        let mut xof = XofStream128::new();
+        xof.update(input);
+        Self::sample_ntt_from_xof(&mut xof)
+    }
+
+    /// Absorbs `rho` once so that a whole matrix can be sampled from clones
+    /// of the returned context with [`NttElement::sample_ntt_from_ctx`].
+    ///
+    /// This saves k² - 1 absorptions of `rho`, but no Keccak permutations:
+    /// `rho || i || j` is 34 bytes and fits in one 168-byte SHAKE-128 block
+    /// either way, while rejection sampling squeezes at least 3 blocks per
+    /// element. Expect a low single-digit percentage gain at best.
+    pub fn sample_ntt_init_rho(rho: &[u8]) -> XofStream128 {
+        let mut ctx = XofStream128::new();
+        ctx.update(rho);
+        ctx
+    }
+
+    /// Finishes `XOF(ρ, i, j)` on a context from
+    /// [`NttElement::sample_ntt_init_rho`] and samples from it.
+    pub fn sample_ntt_from_ctx(mut ctx: XofStream128, ii: usize, jj: usize) -> NttElement {
+        debug_assert!(ii < 256 && jj < 256, "matrix index out of range");
+        ctx.update(&[ii as u8, jj as u8]);
+        Self::sample_ntt_from_xof(&mut ctx.finalize_xof())
+    }
+
+    /// Row `row` of `A_hat` alone: entry `j` is `SampleNTT(rho || j || row)`,
+    /// matching [`AHatMatrix::from_rho`].
+    ///
+    /// For streaming matrix-vector products that hold one row at a time.
+    ///
+    /// [`AHatMatrix::from_rho`]: super::matrix::AHatMatrix::from_rho
+    pub fn sample_ntt_row(rho: &[u8], row: usize, k: usize) -> Vec<NttElement> {
+        let ctx = Self::sample_ntt_init_rho(rho);
+        (0..k)
+            .map(|j| Self::sample_ntt_from_ctx(ctx.clone(), j, row))
+            .collect()
+    }
+
+    /// Hashes an arbitrary byte string to an element of T_q, modelling a
+    /// random oracle `H: {0,1}* -> T_q`.
+    ///
+    /// The XOF input is `SHAKE-128(len(domain_sep) || domain_sep || data)`,
+    /// where the length is a single byte. The length prefix keeps the
+    /// encoding injective, so `("ab", "c")` and `("a", "bc")` do not
+    /// collide. Use a distinct `domain_sep` per protocol and purpose. Unlike
+    /// [`NttElement::sample_ntt`], no matrix indices are absorbed.
+    ///
+    /// # Panics
+    ///
+    /// If `domain_sep` is longer than 255 bytes.
+    pub fn hash_to_ntt_element(data: &[u8], domain_sep: &[u8]) -> NttElement {
+        let tag_len = u8::try_from(domain_sep.len()).expect("domain separator over 255 bytes");
+        let mut xof = XofStream128::new();
+        xof.update(&[tag_len]);
+        xof.update(domain_sep);
+        xof.update(data);
+        Self::sample_ntt_from_xof(&mut xof)
+    }
 
+    /// An element with every coefficient drawn uniformly from `[0, q)`.
+    ///
+    /// Each coefficient is a 12-bit value from `rng`, rejected and redrawn
+    /// if it is `>= q`. Intended for generating test inputs, so it is only
+    /// available in tests or with the `testing` feature.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn from_rng<R: RngCore + CryptoRng>(rng: &mut R) -> NttElement {
+        let mut coefs = [F::zero(); n];
+        for c in coefs.iter_mut() {
+            *c = loop {
+                let v = (rng.next_u32() & MASK_12) as u16;
+                if v < q {
+                    break F::from(v);
+                }
+            };
+        }
+        NttElement { coefs }
+    }
+
+    /// Debug-only sanity check that the coefficients look like a uniform
+    /// element of T_q: all reduced, and spread over all eight equal slices
+    /// of `0..q` (each expected to hold 32 of the 256 values).
+    ///
+    /// This is a statistical smoke test, not a proof. A uniform element fails
+    /// it with probability below 2^-20; structured inputs such as `zero()`
+    /// or the NTT of a sparse polynomial legitimately fail it. Does nothing
+    /// in release builds.
+    pub fn assert_ntt_domain_statistics(&self) {
+        if cfg!(debug_assertions) {
+            let mut buckets = [0usize; 8];
+            for c in self.coefs.iter() {
+                debug_assert!(c.val() < q, "unreduced coefficient {}", c.val());
+                buckets[usize::from(c.val()) * 8 / usize::from(q)] += 1;
+            }
+            for (i, &count) in buckets.iter().enumerate() {
+                debug_assert!(
+                    (4..=72).contains(&count),
+                    "bucket {i} holds {count} of 256 coefficients"
+                );
+            }
+        }
+    }
+
+    /// Rejection-samples a uniform element of T_q from an already
+    /// initialized XOF reader.
+    ///
+    /// This lets callers absorb whatever prefix they need before sampling;
+    /// [`NttElement::sample_ntt`] is the FIPS 203 `XOF(ρ, i, j)` case.
+    pub fn sample_ntt_from_xof<R: XofReader>(reader: &mut R) -> NttElement {
         let mut a = NttElement::zero();
         let mut j = 0usize;
         let mut buf = [0u8; 24];
@@ -79,20 +271,56 @@ impl NttElement {
                 j += 1;
             }
         }
+        // Rejection sampling only accepts values below q, so this is a no-op
+        // that records the guarantee: the output is canonical.
+        a.normalize();
         a
     }
 
-    fn multiply_ntts(&self, other: Self) -> Self {
+    /// Addition in T_q, coefficient by coefficient.
+    ///
+    /// The NTT is linear, so this corresponds to adding the polynomials in
+    /// `R_q = Z_q[X]/(X^256 + 1)`.
+    pub fn pointwise_add(self, other: Self) -> Self {
+        let mut coefficients = [F::zero(); n];
+        for (i, item) in self.coefs.iter().enumerate() {
+            coefficients[i] = *item + other.coefs[i];
+        }
+        NttElement {
+            coefs: coefficients,
+        }
+    }
+
+    /// Multiplication in T_q, which corresponds to polynomial multiplication
+    /// in `R_q`.
+    ///
+    /// "Pointwise" is per coefficient pair, not per coefficient; see
+    /// [`NttElement::hadamard_product`].
+    pub fn pointwise_mul(self, other: Self) -> Self {
+        self.hadamard_product(other)
+    }
+
+    /// `MultiplyNTTs`: the coefficient-wise product in T_q.
+    ///
+    /// Since the NTT is a ring isomorphism, this is polynomial multiplication
+    /// in `Z_q[X]/(X^256 + 1)`: `INTT(NTT(f).hadamard_product(NTT(g))) = f * g`.
+    /// Strictly, T_q pairs coefficients and multiplies each pair as a
+    /// degree-one polynomial, so "element-wise" means per pair.
+    pub fn hadamard_product(self, other: Self) -> Self {
+        NttElement::base_case_multiply_all(&self, &other)
+    }
+
+    /// Runs `BaseCaseMultiply` over all 128 coefficient pairs.
+    pub fn base_case_multiply_all(a: &NttElement, b: &NttElement) -> NttElement {
         let mut h_hat = NttElement::zero();
 
-        // Iterate over `K_MOD_ROOTS` with their indices
-        for (i, &k_mod_root) in K_MOD_ROOTS.iter().enumerate() {
+        for (i, &gamma) in K_MOD_ROOTS.iter().enumerate() {
             (h_hat.coefs[2 * i], h_hat.coefs[2 * i + 1]) = NttElement::base_case_multiply(
-                self.coefs[2 * i],
-                self.coefs[(2 * i) + 1],
-                other.coefs[2 * i],
-                other.coefs[(2 * i) + 1],
-                k_mod_root,
+                a.coefs[2 * i],
+                a.coefs[2 * i + 1],
+                b.coefs[2 * i],
+                b.coefs[2 * i + 1],
+                gamma,
             );
         }
 
@@ -124,8 +352,25 @@ impl NttElement {
         }
     }
 
+    /// Debugging aid: checks that `NTT(NTT^-1(self))` gives back `self`.
+    ///
+    /// The NTT is a bijection on reduced elements, so this fails only if a
+    /// coefficient is unreduced or a transform is broken. It costs a full
+    /// inverse and forward transform, so call it from `debug_assert!`.
+    pub(crate) fn verify_ntt_consistency(&self) -> bool {
+        let mut copy = *self;
+        NttElement::from_ring_element(copy.ntt_inv()).coefs == self.coefs
+    }
+
+    /// `NTT^-1(self)`, computed on a copy so that the caller's element is
+    /// left in T_q.
+    pub fn to_ring_element(self) -> RingElement {
+        let mut copy = self;
+        copy.ntt_inv()
+    }
+
     // This should only be used when converting to Rq
-    pub fn ntt_inv(&mut self) -> RingElement {
+    pub(crate) fn ntt_inv(&mut self) -> RingElement {
         let mut k = 127;
         let mut len = 2;
         while len <= 128 {
@@ -142,56 +387,56 @@ impl NttElement {
             len *= 2;
         }
         for item in &mut self.coefs {
-            *item = *item * 3303;
+            *item = *item * F::INV_N.val();
         }
         RingElement::new(self.coefs)
     }
 
+    /// `ByteEncode_12`, appending the 384 encoded bytes to `b`.
     pub fn byte_encode_12(&self, mut b: Vec<u8>) -> Vec<u8> {
-        b.reserve(ENCODE_12);
-        let mut cursor = b.len();
-        b.resize(b.len() + ENCODE_12, 0);
-
-        for i in (0..n).step_by(2) {
-            let x = self.coefs[i].val() as u32 | (self.coefs[i + 1].val() as u32) << 12;
-            b[cursor] = (x & 0xFF) as u8;
-            b[cursor + 1] = ((x >> 8) & 0xFF) as u8;
-            b[cursor + 2] = ((x >> 16) & 0xFF) as u8;
-            cursor += 3;
+        // Both sides are fixed-size chunks of a fixed-size array, so the
+        // compiler can drop every bounds check without any `unsafe`.
+        let mut out = [0u8; ENCODE_12];
+        for (group, pair) in out.chunks_exact_mut(3).zip(self.coefs.chunks_exact(2)) {
+            let x = u32::from(pair[0].val()) | (u32::from(pair[1].val()) << 12);
+            group.copy_from_slice(&x.to_le_bytes()[..3]);
         }
+        b.extend_from_slice(&out);
         b
     }
 
+    /// `ByteDecode_12`, rejecting any coefficient `>= q`.
+    ///
+    /// # Errors
+    ///
+    /// [`KemError::EncodingError`] if `b` is not 384 bytes long, and
+    /// [`KemError::BadCoefficient`] with the byte offset of the first 3-byte
+    /// group holding an out-of-range coefficient.
+    #[must_use = "decoding errors are reported through the Result"]
     pub fn byte_decode_12(b: &[u8]) -> Result<Self> {
         if b.len() != (ENCODE_12) {
             return Err(KemError::EncodingError);
         }
 
-        let mut f = Vec::with_capacity(n);
-
-        let mut i = 0;
-        while i < b.len() {
-            let d = u32::from(b[i]) | (u32::from(b[i + 1]) << 8) | (u32::from(b[i + 2]) << 16);
-            let elem1 = F::new((d & MASK_12) as u16)
-                .check_reduced()
-                .map_err(|_| KemError::EncodingError)?;
-
-            let elem2 = F::new((d >> 12) as u16)
-                .check_reduced()
-                .map_err(|_| KemError::EncodingError)?;
-
-            f.push(elem1);
-            f.push(elem2);
-
-            i += 3;
+        let mut f = NttElement::zero();
+        for (i, group) in b.chunks_exact(3).enumerate() {
+            let d = u32::from(group[0]) | (u32::from(group[1]) << 8) | (u32::from(group[2]) << 16);
+            let pair = [(d & MASK_12) as u16, (d >> 12) as u16];
+            for (j, value) in pair.into_iter().enumerate() {
+                // The early return and the error both reveal where the
+                // first bad coefficient is. That is harmless for `ek`, but
+                // callers decoding the secret `dk_pke` must map the error
+                // to a generic one.
+                if value >= q {
+                    return Err(KemError::BadCoefficient {
+                        position: 3 * i,
+                        value,
+                    });
+                }
+                f.coefs[2 * i + j] = F::from(value);
+            }
         }
-        let coefficients: [F; n] = f
-            .try_into()
-            .map_err(|_| KemError::EncodingError)?;
-
-        Ok(Self {
-            coefs: coefficients,
-        })
+        Ok(f)
     }
 }
 
@@ -199,19 +444,7 @@ impl Add for NttElement {
     type Output = Self;
 
     fn add(self, other: Self) -> Self::Output {
-        assert_eq!(
-            self.coefs.len(),
-            other.coefs.len(),
-            "RingElements must be of the same length"
-        );
-
-        let mut coefficients = [F::zero(); n];
-        for (i, item) in self.coefs.iter().enumerate().take(n) {
-            coefficients[i] = *item + other.coefs[i];
-        }
-        NttElement {
-            coefs: coefficients,
-        }
+        self.pointwise_add(other)
     }
 }
 
@@ -223,9 +456,26 @@ impl AddAssign for NttElement {
     }
 }
 
+impl SubAssign for NttElement {
+    fn sub_assign(&mut self, other: Self) {
+        for (lhs, rhs) in self.coefs.iter_mut().zip(other.coefs.iter()) {
+            *lhs = *lhs - *rhs;
+        }
+    }
+}
+
+impl Sum for NttElement {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(NttElement::zero(), |mut acc, elem| {
+            acc += elem;
+            acc
+        })
+    }
+}
+
 impl From<RingElement> for NttElement {
-    fn from(mut val: RingElement) -> Self {
-        NttElement::new(&mut val)
+    fn from(val: RingElement) -> Self {
+        NttElement::from_ring_element(val)
     }
 }
 
@@ -239,7 +489,7 @@ impl Mul<NttElement> for NttElement {
     type Output = Self;
 
     fn mul(self, rhs: NttElement) -> Self::Output {
-        self.multiply_ntts(rhs)
+        self.pointwise_mul(rhs)
     }
 }
 
@@ -247,7 +497,7 @@ impl fmt::Debug for NttElement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for (index, element) in self.coefs.iter().enumerate() {
             // adjust for spacing between rows
-            write!(f, "{:<8}", element.val())?;
+            write!(f, "{:04X}    ", element)?;
             // Adjust for modulus for row width
             if (index + 1) % 8 == 0 {
                 writeln!(f)?;
@@ -256,3 +506,401 @@ impl fmt::Debug for NttElement {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::NttElement;
+
+    #[test]
+    fn from_ntt_coefficients_skips_transform() {
+        use crate::math::ring_element::RingElement;
+
+        let r = RingElement::sample_poly_cbd_eta2(&[3u8; 32], 1);
+        let t = NttElement::from_ring_element(r);
+        assert_eq!(NttElement::from_ntt_coefficients(t.coefs).coefs, t.coefs);
+        assert_ne!(t.coefs, r.coefs);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn ntt_of_ring_element_is_consistent() {
+        use crate::math::ring_element::RingElement;
+
+        for b in 0..4 {
+            let r = RingElement::sample_poly_cbd_eta2(&[b; 32], b);
+            assert!(NttElement::from(r).verify_ntt_consistency());
+        }
+        assert!(NttElement::sample_ntt(&[1u8; 32], 0, 0).verify_ntt_consistency());
+    }
+
+    #[test]
+    fn add_scaled_matches_scale_then_add() {
+        use super::F;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0x1164);
+        for s in [0, 1, 2, 1664, 3328] {
+            let s = F::new(s);
+            let acc = NttElement::from_rng(&mut rng);
+            let x = NttElement::from_rng(&mut rng);
+
+            let mut expected = x;
+            expected.apply_fn(|c| c * s);
+            expected += acc;
+
+            let mut got = acc;
+            got.add_scaled(s, &x);
+            assert_eq!(got.coefs, expected.coefs);
+        }
+    }
+
+    #[test]
+    fn hash_to_ntt_element_separates_domains() {
+        let h = NttElement::hash_to_ntt_element(b"c", b"ab");
+        assert_eq!(h.coefs, NttElement::hash_to_ntt_element(b"c", b"ab").coefs);
+        assert_ne!(h.coefs, NttElement::hash_to_ntt_element(b"bc", b"a").coefs);
+        assert_ne!(h.coefs, NttElement::hash_to_ntt_element(b"c", b"ac").coefs);
+        h.assert_ntt_domain_statistics();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "blake3-xof", ignore = "pinned to SHAKE output")]
+    fn sample_ntt_matches_xof_reader() {
+        use sha3::{
+            digest::{ExtendableOutput, Update},
+            Shake128,
+        };
+
+        let rho = [9u8; 32];
+        let mut hasher = Shake128::default();
+        hasher.update(&rho);
+        hasher.update(&[1, 2]);
+        let mut reader = hasher.finalize_xof();
+
+        assert_eq!(
+            NttElement::sample_ntt_from_xof(&mut reader).coefs,
+            NttElement::sample_ntt(&rho, 1, 2).coefs
+        );
+
+        let mut input = [9u8; 34];
+        input[32..].copy_from_slice(&[1, 2]);
+        assert_eq!(
+            NttElement::sample_ntt_from_bytes(&input).coefs,
+            NttElement::sample_ntt(&rho, 1, 2).coefs
+        );
+    }
+
+    #[test]
+    fn sample_ntt_output_looks_uniform() {
+        for i in 0..4 {
+            for j in 0..4 {
+                NttElement::sample_ntt(&[i as u8; 32], i, j).assert_ntt_domain_statistics();
+            }
+        }
+    }
+
+    #[test]
+    fn from_rng_output_looks_uniform() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0x1159_0001);
+        for _ in 0..16 {
+            NttElement::from_rng(&mut rng).assert_ntt_domain_statistics();
+        }
+    }
+
+    #[test]
+    fn sample_ntt_passes_chi_squared() {
+        use crate::constants::ml_kem_constants::q;
+
+        // 2560 draws are too few for one bin per value, so group [0, q)
+        // into 32 buckets; bucket sizes differ by one, so each gets its
+        // own expected count. The p = 0.001 critical value of chi² with
+        // 31 degrees of freedom is 61.098.
+        const BUCKETS: usize = 32;
+        let bucket = |v: u16| usize::from(v) * BUCKETS / usize::from(q);
+
+        let mut observed = [0usize; BUCKETS];
+        let mut width = [0usize; BUCKETS];
+        for v in 0..q {
+            width[bucket(v)] += 1;
+        }
+        for seed in 0..10u8 {
+            let a = NttElement::sample_ntt(&[seed; 32], usize::from(seed), 0);
+            for c in a.coefs.iter() {
+                observed[bucket(c.val())] += 1;
+            }
+        }
+
+        let total = 2560.0;
+        let chi2: f64 = observed
+            .iter()
+            .zip(width.iter())
+            .map(|(&o, &w)| {
+                let expected = total * w as f64 / f64::from(q);
+                let d = o as f64 - expected;
+                d * d / expected
+            })
+            .sum();
+        assert!(chi2 < 61.098, "chi² = {chi2}");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn zero_fails_ntt_domain_statistics() {
+        NttElement::zero().assert_ntt_domain_statistics();
+    }
+
+    #[test]
+    fn sample_ntt_from_ctx_matches_sample_ntt() {
+        let rho = [5u8; 32];
+        let ctx = NttElement::sample_ntt_init_rho(&rho);
+        for (i, j) in [(0, 0), (0, 1), (2, 1)] {
+            assert_eq!(
+                NttElement::sample_ntt_from_ctx(ctx.clone(), i, j).to_u16_array(),
+                NttElement::sample_ntt(&rho, i, j).to_u16_array()
+            );
+        }
+    }
+
+    #[test]
+    fn base_case_multiply_all_matches_pairwise() {
+        use crate::constants::K_MOD_ROOTS;
+
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0x1159);
+        let a = NttElement::from_rng(&mut rng);
+        let b = NttElement::from_rng(&mut rng);
+        let h = NttElement::base_case_multiply_all(&a, &b);
+        for (i, &gamma) in K_MOD_ROOTS.iter().enumerate() {
+            let (c_0, c_1) = NttElement::base_case_multiply(
+                a.coefs[2 * i],
+                a.coefs[2 * i + 1],
+                b.coefs[2 * i],
+                b.coefs[2 * i + 1],
+                gamma,
+            );
+            assert_eq!((h.coefs[2 * i], h.coefs[2 * i + 1]), (c_0, c_1));
+        }
+    }
+
+    #[test]
+    fn u16_array_roundtrip() {
+        use crate::constants::ml_kem_constants::q;
+
+        let mut vals = [0u16; 256];
+        for (i, v) in vals.iter_mut().enumerate() {
+            *v = (i as u16 * 13) % q;
+        }
+        vals[255] = q - 1;
+        let a = NttElement::from_u16_array(vals).unwrap();
+        assert_eq!(a.to_u16_array(), vals);
+
+        vals[7] = q + 5;
+        assert_eq!(NttElement::from_u16_array(vals).unwrap().coefs[7].val(), 5);
+        vals[7] = 2 * q;
+        assert!(NttElement::from_u16_array(vals).is_err());
+    }
+
+    #[test]
+    fn byte_encode_12_roundtrips() {
+        use crate::constants::ml_kem_constants::q;
+
+        let mut edge = NttElement::from_u16_array([q - 1; 256]).unwrap();
+        edge.coefs[0] = super::F::zero();
+        for i in 0..256usize {
+            let a = if i == 0 {
+                edge
+            } else {
+                NttElement::sample_ntt(&[i as u8; 32], i, i / 7)
+            };
+            // appends after existing bytes
+            let bytes = a.byte_encode_12(alloc::vec![0xAA; i % 5]);
+            assert_eq!(bytes.len(), i % 5 + 384);
+            assert!(bytes[..i % 5].iter().all(|&x| x == 0xAA));
+            assert_eq!(
+                NttElement::byte_decode_12(&bytes[i % 5..]).unwrap().coefs,
+                a.coefs
+            );
+        }
+    }
+
+    #[test]
+    fn byte_decode_12_reports_bad_coefficient() {
+        use crate::error::KemError;
+
+        let a = NttElement::sample_ntt(&[1u8; 32], 0, 0);
+        let mut bytes = a.byte_encode_12(alloc::vec::Vec::new());
+        assert_eq!(NttElement::byte_decode_12(&bytes).unwrap().coefs, a.coefs);
+
+        // high coefficient of group 5 := 0xFFF
+        bytes[16] |= 0xF0;
+        bytes[17] = 0xFF;
+        assert_eq!(
+            NttElement::byte_decode_12(&bytes).unwrap_err(),
+            KemError::BadCoefficient {
+                position: 15,
+                value: 0xFFF
+            }
+        );
+        assert_eq!(
+            NttElement::byte_decode_12(&bytes[1..]).unwrap_err(),
+            KemError::EncodingError
+        );
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn byte_encode_12_roundtrips_any_reduced_element(
+            vals in proptest::collection::vec(0..super::q, super::n)
+        ) {
+            let a = NttElement::from_u16_array(vals.try_into().unwrap()).unwrap();
+            let bytes = a.byte_encode_12(alloc::vec::Vec::new());
+            proptest::prop_assert_eq!(bytes.len(), 384);
+            proptest::prop_assert_eq!(NttElement::byte_decode_12(&bytes).unwrap().coefs, a.coefs);
+        }
+
+        #[test]
+        fn byte_decode_12_is_canonical(
+            bytes in proptest::collection::vec(proptest::num::u8::ANY, 384)
+        ) {
+            use crate::error::KemError;
+
+            // Decoding either finds a coefficient >= q or gives an element
+            // that encodes back to the same bytes.
+            match NttElement::byte_decode_12(&bytes) {
+                Ok(a) => {
+                    proptest::prop_assert_eq!(a.byte_encode_12(alloc::vec::Vec::new()), bytes)
+                }
+                Err(KemError::BadCoefficient { value, .. }) => {
+                    proptest::prop_assert!(value >= super::q)
+                }
+                Err(e) => proptest::prop_assert!(false, "unexpected error {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn hadamard_product_is_ring_multiplication() {
+        use crate::math::ring_element::RingElement;
+
+        let f = RingElement::sample_poly_cbd_eta2(&[1u8; 32], 0);
+        let g = RingElement::sample_poly_cbd_eta2(&[1u8; 32], 1);
+        let fg = NttElement::from(f).hadamard_product(NttElement::from(g));
+        assert_eq!(RingElement::from(fg), f.convolution_mul(g));
+    }
+
+    #[test]
+    fn to_ring_element_keeps_self() {
+        use crate::math::ring_element::RingElement;
+
+        let a = NttElement::sample_ntt(&[4u8; 32], 2, 1);
+        let before = a.coefs;
+        assert_eq!(a.to_ring_element(), RingElement::from(a));
+        assert_eq!(a.coefs, before);
+    }
+
+    #[test]
+    fn pointwise_ops_match_ring_ops() {
+        use crate::math::ring_element::RingElement;
+
+        let f = RingElement::sample_poly_cbd_eta2(&[2u8; 32], 0);
+        let g = RingElement::sample_poly_cbd_eta2(&[2u8; 32], 1);
+        let (a, b) = (NttElement::from(f), NttElement::from(g));
+        assert_eq!(RingElement::from(a.pointwise_add(b)), f + g);
+        assert_eq!(RingElement::from(a.pointwise_mul(b)), f.convolution_mul(g));
+        assert_eq!((a + b).coefs, a.pointwise_add(b).coefs);
+    }
+
+    #[test]
+    fn sum_matches_add_assign() {
+        let xs: [NttElement; 3] =
+            core::array::from_fn(|i| NttElement::sample_ntt(&[2u8; 32], i, 0));
+        let mut expected = NttElement::zero();
+        for x in xs {
+            expected += x;
+        }
+        assert_eq!(xs.into_iter().sum::<NttElement>().coefs, expected.coefs);
+        assert!(core::iter::empty::<NttElement>()
+            .sum::<NttElement>()
+            .is_zero());
+    }
+
+    #[test]
+    fn apply_fn() {
+        use crate::math::ring_element::RingElement;
+
+        let a = NttElement::sample_ntt(&[6u8; 32], 3, 3);
+        let mut b = a;
+        b.apply_fn(|x| x);
+        assert_eq!(b.coefs, a.coefs);
+        b.apply_fn(|x| x * super::F::new(2));
+        assert_eq!(b.coefs, (a + a).coefs);
+
+        let r = RingElement::from(a);
+        let mut s = r;
+        s.apply_fn(|x| x);
+        assert_eq!(s, r);
+        s.apply_fn(|_| super::F::ZERO);
+        assert!(s.is_zero());
+    }
+
+    #[test]
+    fn sub_assign_self_is_zero() {
+        for i in 0..8 {
+            let mut a = NttElement::sample_ntt(&[i as u8; 32], i, 0);
+            let b = NttElement::sample_ntt(&[i as u8; 32], i, 1);
+            let mut c = a + b;
+            c -= b;
+            assert_eq!(c.coefs, a.coefs);
+            a -= a;
+            assert!(a.is_zero());
+        }
+    }
+
+    #[test]
+    fn normalize_reduces_below_q() {
+        use crate::constants::ml_kem_constants::q;
+
+        let mut a = NttElement {
+            coefs: core::array::from_fn(|i| super::F::from((i as u16 * 26) % (2 * q))),
+        };
+        a.normalize();
+        assert!(a.coefs.iter().all(|c| c.val() < q));
+        for (i, c) in a.coefs.iter().enumerate() {
+            assert_eq!(c.val(), (i as u16 * 26) % (2 * q) % q);
+        }
+    }
+
+    #[test]
+    fn default_is_zero() {
+        assert!(NttElement::default().is_zero());
+    }
+
+    #[test]
+    fn clone_from_copies_coefficients() {
+        let a = NttElement::sample_ntt(&[3u8; 32], 1, 1);
+        let mut b = NttElement::zero();
+        b.clone_from(&a);
+        assert_eq!(b.coefs, a.coefs);
+    }
+
+    #[test]
+    fn is_zero_predicates() {
+        use crate::math::ring_element::RingElement;
+
+        assert!(NttElement::zero().is_zero());
+        assert!(bool::from(NttElement::zero().ct_is_zero()));
+        assert!(RingElement::zero().is_zero());
+        assert!(bool::from(RingElement::zero().ct_is_zero()));
+
+        let a = NttElement::sample_ntt(&[8u8; 32], 0, 0);
+        assert!(!a.is_zero());
+        assert!(!bool::from(a.ct_is_zero()));
+        let r = RingElement::from(a);
+        assert!(!r.is_zero());
+        assert!(!bool::from(r.ct_is_zero()));
+    }
+}
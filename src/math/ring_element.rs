@@ -1,18 +1,31 @@
-use crate::{constants::ml_kem_constants::n, math::field_element::FieldElement as F};
+use crate::{
+    constants::ml_kem_constants::{n, q},
+    fips203::primitives::{prf, prf_into},
+    math::{
+        encoding::{Compress, CompressionFactor, Encode},
+        field_element::FieldElement as F,
+        ntt_element::NttElement,
+        util::ZeroizingClone,
+    },
+};
+use alloc::{collections::BTreeMap, vec::Vec};
 use core::{
     fmt,
     iter::Sum,
-    ops::{Add, AddAssign, Sub},
-};
-use sha3::{
-    digest::{ExtendableOutput, Update, XofReader},
-    Shake256,
+    ops::{Add, AddAssign, Mul, MulAssign, Sub},
 };
-use typenum::Unsigned;
+use sha3::digest::XofReader;
+use subtle::{Choice, ConstantTimeEq};
+use typenum::{Unsigned, U1};
+use zeroize::{Zeroize, Zeroizing};
+
+/// `eta_1` and `eta_2` are at most 3 in every ML-KEM parameter set, which
+/// bounds the CBD sampling buffers at `64 * 3` bytes.
+const MAX_ETA: usize = 3;
 
 /// A polynomial is an element of the ring R. It is an array of 256 coefficients
 /// which themselves are [F].
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Zeroize)]
 pub struct RingElement {
     pub coefs: [F; n],
 }
@@ -33,39 +46,256 @@ impl RingElement {
         [F::new(0); n].into()
     }
 
+    /// Wraps raw coefficient values without reducing them.
+    ///
+    /// Coefficients of a ring element are allowed to sit in `[0, 2q)` between
+    /// operations, so no range check is done; arithmetic reduces as needed.
+    pub fn from_u16_array(vals: [u16; n]) -> Self {
+        RingElement::new(vals.map(F::from))
+    }
+
+    /// The raw coefficient values, which may be in `[0, 2q)` if the element
+    /// has not been reduced.
+    pub fn to_u16_array(self) -> [u16; n] {
+        self.coefs.map(F::val)
+    }
+
+    /// Number of non-zero coefficients.
+    ///
+    /// For a decompressed message (d = 1) every coefficient is 0 or
+    /// round(q/2), so this is the number of set bits in the message.
+    pub fn hamming_weight(&self) -> u32 {
+        self.coefs.iter().filter(|c| c.val() != 0).count() as u32
+    }
+
+    /// A compressed copy of `self`, leaving `self` unchanged.
+    pub(crate) fn compress_copy<D: CompressionFactor>(&self) -> Self {
+        let mut r = *self;
+        r.compress::<D>();
+        r
+    }
+
+    /// A decompressed copy of `self`, leaving `self` unchanged.
+    pub(crate) fn decompress_copy<D: CompressionFactor>(&self) -> Self {
+        let mut r = *self;
+        r.decompress::<D>();
+        r
+    }
+
+    /// Replaces every coefficient `c` with `f(c)`.
+    pub fn apply_fn(&mut self, f: impl Fn(F) -> F) {
+        self.map_coefficients_inplace(f);
+    }
+
+    /// A new element with every coefficient `c` replaced by `f(c)`.
+    pub fn map_coefficients<G: Fn(F) -> F>(&self, f: G) -> Self {
+        RingElement::new(self.coefs.map(f))
+    }
+
+    /// In-place variant of [`RingElement::map_coefficients`].
+    pub fn map_coefficients_inplace<G: Fn(F) -> F>(&mut self, f: G) {
+        for c in self.coefs.iter_mut() {
+            *c = f(*c);
+        }
+    }
+
+    /// Returns true iff every coefficient is zero.
+    pub fn is_zero(&self) -> bool {
+        self.coefs.iter().all(|c| c.val() == 0)
+    }
+
+    /// Constant-time variant of [`RingElement::is_zero`].
+    pub fn ct_is_zero(&self) -> Choice {
+        self.coefs
+            .iter()
+            .fold(0u16, |acc, c| acc | c.val())
+            .ct_eq(&0)
+    }
+
+    /// Builds a ring element from signed coefficients, mapping each `v` to
+    /// `v mod q` in `[0, q)` so that e.g. `-1` becomes `q - 1`.
+    pub fn from_signed_coefficients(vals: [i16; n]) -> Self {
+        let mut coefs = [F::zero(); n];
+        for (c, v) in coefs.iter_mut().zip(vals.iter()) {
+            *c = F::from(i32::from(*v).rem_euclid(i32::from(q)) as u16);
+        }
+        RingElement::new(coefs)
+    }
+
+    /// The infinity norm `max |c|` over the centered coefficients.
+    pub fn infinity_norm(&self) -> u16 {
+        self.coefs
+            .iter()
+            .map(|c| c.centered().unsigned_abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The squared Euclidean norm `sum c²` over the centered coefficients.
+    pub fn l2_norm_squared(&self) -> u64 {
+        self.coefs
+            .iter()
+            .map(|c| {
+                let c = i64::from(c.centered());
+                (c * c) as u64
+            })
+            .sum()
+    }
+
+    /// Histogram of the centered coefficient values across all `samples`,
+    /// sorted by value.
+    ///
+    /// Used to check the output distribution of the CBD samplers.
+    pub fn coefficient_frequency_distribution(samples: &[RingElement]) -> Vec<(i16, usize)> {
+        let mut counts = BTreeMap::new();
+        for c in samples.iter().flat_map(|r| r.coefs.iter()) {
+            *counts.entry(c.centered()).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+
+    /// The inner product `sum a_i * b_i mod q` of the coefficient vectors.
+    ///
+    /// This treats the elements as vectors in `Z_q^n`; it is not
+    /// polynomial multiplication in `R_q`. Each product is Barrett reduced
+    /// before it is accumulated.
+    pub fn scalar_product(self, other: Self) -> F {
+        self.coefs
+            .iter()
+            .zip(other.coefs.iter())
+            .fold(F::zero(), |acc, (a, b)| acc + *a * *b)
+    }
+
+    /// Multiplies two ring elements directly as polynomials in
+    /// `Z_q[X]/(X^n + 1)` using the schoolbook method.
+    ///
+    /// Products landing at index `i + j >= n` wrap around with negation
+    /// since `X^n = -1`. This is O(n²) and exists as a reference for the
+    /// NTT-based multiplication, not for use on the hot path.
+    pub fn convolution_mul(self, other: Self) -> Self {
+        let mut result = [F::zero(); n];
+        for (i, a) in self.coefs.iter().enumerate() {
+            for (j, b) in other.coefs.iter().enumerate() {
+                let prod = *a * *b;
+                if i + j < n {
+                    result[i + j] += prod;
+                } else {
+                    result[i + j - n] = result[i + j - n] - prod;
+                }
+            }
+        }
+        RingElement::new(result)
+    }
+
+    /// `SamplePolyCBD_eta(PRF_eta(s, b))`.
+    ///
+    /// # Panics
+    ///
+    /// If `eta > 3`, which no ML-KEM parameter set uses.
     pub fn sample_poly_cbd<Eta: Unsigned>(s: &[u8], b: u8) -> RingElement {
-        let mut prf = Shake256::default();
-        prf.update(s);
-        prf.update(&[b]);
+        let mut buf = Zeroizing::new([0u8; 64 * MAX_ETA]);
+        let buf = &mut buf[..64 * Eta::USIZE];
+        prf_into(s, b, buf);
+        Self::sample_poly_cbd_from_bytes::<Eta>(buf)
+    }
 
+    /// Samples a CBD polynomial from an already initialized XOF reader.
+    ///
+    /// [`RingElement::sample_poly_cbd`] is the `PRF_eta(s, b)` case.
+    ///
+    /// # Panics
+    ///
+    /// If `eta > 3`.
+    pub fn sample_poly_cbd_from_xof<Eta: Unsigned, R: XofReader>(reader: &mut R) -> RingElement {
         // Buffer size is 64 * eta
-        let buf_size = 64 * Eta::USIZE;
-        let mut buf = alloc::vec![0u8; buf_size];
-        let mut reader = prf.finalize_xof();
-        reader.read(&mut buf);
+        let mut buf = Zeroizing::new([0u8; 64 * MAX_ETA]);
+        let buf = &mut buf[..64 * Eta::USIZE];
+        reader.read(buf);
+        Self::sample_poly_cbd_from_bytes::<Eta>(buf)
+    }
 
+    // FIPS 203 (DRAFT), Algorithm 7 on 64 * eta bytes of PRF output.
+    fn sample_poly_cbd_from_bytes<Eta: Unsigned>(buf: &[u8]) -> RingElement {
+        let eta = Eta::USIZE;
+        let bit = |i: usize| u16::from((buf[i / 8] >> (i % 8)) & 1);
+
+        // Coefficient i uses bits [2i*eta, 2i*eta + eta) for x and the next
+        // eta bits for y.
         let mut f = [F::new(0); n];
+        for (i, coef) in f.iter_mut().enumerate() {
+            let base = 2 * i * eta;
+            let x: u16 = (base..base + eta).map(bit).sum();
+            let y: u16 = (base + eta..base + 2 * eta).map(bit).sum();
+            *coef = F::new(x) - F::new(y);
+        }
+        RingElement::new(f)
+    }
+
+    /// `SamplePolyCBD_3` for a fixed `eta = 3`, the `eta_1` of ML-KEM-512.
+    ///
+    /// Each coefficient is `(x_0 + x_1 + x_2) - (y_0 + y_1 + y_2)` for six
+    /// bits of `PRF_3(s, b)`, so four coefficients are drawn from every
+    /// three bytes.
+    pub fn sample_poly_cbd_eta3(s: &[u8], b: u8) -> RingElement {
+        let buf = prf(s, b, 3);
 
-        for i in (0..n).step_by(2) {
-            // Iterate through indices, stepping by 2.
-            let byte = buf[i / 2];
-            let b_7 = (byte >> 7) & 1;
-            let b_6 = (byte >> 6) & 1;
-            let b_5 = (byte >> 5) & 1;
-            let b_4 = (byte >> 4) & 1;
-            let b_3 = (byte >> 3) & 1;
-            let b_2 = (byte >> 2) & 1;
-            let b_1 = (byte >> 1) & 1;
-            let b_0 = byte & 1;
-
-            f[i] = F::new((b_0 + b_1).into()) - F::new((b_2 + b_3).into());
-            // Ensure i+1 doesn't go out of bounds, relevant if N is odd.
-            if i + 1 < n {
-                f[i + 1] = F::new((b_4 + b_5).into()) - F::new((b_6 + b_7).into());
+        let mut f = [F::zero(); n];
+        for (i, chunk) in buf.chunks_exact(3).enumerate() {
+            let bits = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], 0]);
+            for j in 0..4 {
+                let six = bits >> (6 * j);
+                let x = (six & 1) + ((six >> 1) & 1) + ((six >> 2) & 1);
+                let y = ((six >> 3) & 1) + ((six >> 4) & 1) + ((six >> 5) & 1);
+                f[4 * i + j] = F::new(x as u16) - F::new(y as u16);
             }
         }
         RingElement::new(f)
     }
+
+    /// `SamplePolyCBD_2` for a fixed `eta = 2`, the `eta_2` of every ML-KEM
+    /// parameter set.
+    ///
+    /// Each coefficient is `(x_0 + x_1) - (y_0 + y_1)` for four bits of
+    /// `PRF_2(s, b)`, so two coefficients are drawn from every byte.
+    pub fn sample_poly_cbd_eta2(s: &[u8], b: u8) -> RingElement {
+        let buf = prf(s, b, 2);
+
+        let mut f = [F::zero(); n];
+        for (i, byte) in buf.iter().enumerate() {
+            for j in 0..2 {
+                let nibble = byte >> (4 * j);
+                let x = (nibble & 1) + ((nibble >> 1) & 1);
+                let y = ((nibble >> 2) & 1) + ((nibble >> 3) & 1);
+                f[2 * i + j] = F::new(x.into()) - F::new(y.into());
+            }
+        }
+        RingElement::new(f)
+    }
+}
+
+/// `Decompress_1(ByteDecode_1(m))`: bit `i` of `m` becomes coefficient `i`,
+/// as 0 or `round(q/2)`.
+pub fn message_to_ring_element(m: &[u8; 32]) -> RingElement {
+    let mut r: RingElement = Encode::<U1>::decode(m);
+    r.decompress::<U1>();
+    r
+}
+
+/// `ByteEncode_1(Compress_1(r))`: each coefficient is rounded to the nearer
+/// of 0 and `round(q/2)` and packed as one bit.
+pub fn ring_element_to_message(r: &RingElement) -> [u8; 32] {
+    // `r` is secret on the decryption path, so compress a wiped copy
+    let mut w = r.zeroizing_clone();
+    w.compress::<U1>();
+    let mut m = [0u8; 32];
+    Encode::<U1>::encode_into(&*w, &mut m);
+    m
+}
+
+impl ZeroizingClone for RingElement {
+    fn zeroizing_clone(&self) -> Zeroizing<Self> {
+        Zeroizing::new(*self)
+    }
 }
 
 impl fmt::Debug for RingElement {
@@ -137,6 +367,48 @@ impl Sub for RingElement {
     }
 }
 
+/// Scales every coefficient by `rhs`.
+impl Mul<F> for RingElement {
+    type Output = Self;
+
+    fn mul(mut self, rhs: F) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl MulAssign<F> for RingElement {
+    fn mul_assign(&mut self, rhs: F) {
+        for c in self.coefs.iter_mut() {
+            *c = *c * rhs;
+        }
+    }
+}
+
+/// Polynomial multiplication in `R_q`, computed as
+/// `NTT^-1(NTT(self) ∘ NTT(rhs))`.
+///
+/// This costs two forward transforms and one inverse. When one operand is
+/// reused, convert it to an [`NttElement`] once instead.
+impl Mul<RingElement> for RingElement {
+    type Output = Self;
+
+    fn mul(self, rhs: RingElement) -> Self::Output {
+        (NttElement::from(self) * NttElement::from(rhs)).into()
+    }
+}
+
+/// Compares every coefficient, without short-circuiting on the first
+/// difference.
+impl ConstantTimeEq for RingElement {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.coefs
+            .iter()
+            .zip(other.coefs.iter())
+            .fold(Choice::from(1), |acc, (a, b)| acc & a.val().ct_eq(&b.val()))
+    }
+}
+
 impl PartialEq for RingElement {
     fn eq(&self, other: &Self) -> bool {
         if self.coefs.len() != other.coefs.len() {
@@ -148,3 +420,372 @@ impl PartialEq for RingElement {
             .all(|(a, b)| a == b)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::RingElement;
+    use crate::constants::ml_kem_constants::{n, q};
+    use crate::math::{field_element::FieldElement as F, ntt_element::NttElement};
+
+    fn ntt_mul(a: RingElement, b: RingElement) -> RingElement {
+        (NttElement::from(a) * NttElement::from(b)).into()
+    }
+
+    #[test]
+    fn from_signed_coefficients_lifts_negatives() {
+        let mut vals = [0i16; n];
+        vals[0] = -1;
+        vals[2] = 1;
+        vals[3] = -2;
+        vals[4] = i16::MIN;
+        vals[5] = i16::MAX;
+
+        let r = RingElement::from_signed_coefficients(vals);
+        assert_eq!(r.coefs[0].val(), 3328);
+        assert_eq!(r.coefs[1].val(), 0);
+        assert_eq!(r.coefs[2].val(), 1);
+        assert_eq!(r.coefs[3].val(), 3327);
+        assert_eq!(r.coefs[4].val(), (-32768i32).rem_euclid(3329) as u16);
+        assert_eq!(r.coefs[5].val(), 32767 % 3329);
+    }
+
+    #[test]
+    fn concrete_cbd_matches_generic() {
+        for b in 0..16 {
+            assert_eq!(
+                RingElement::sample_poly_cbd_eta2(&[b; 32], b),
+                RingElement::sample_poly_cbd::<typenum::U2>(&[b; 32], b)
+            );
+            assert_eq!(
+                RingElement::sample_poly_cbd_eta3(&[b; 32], b),
+                RingElement::sample_poly_cbd::<typenum::U3>(&[b; 32], b)
+            );
+        }
+    }
+
+    #[test]
+    fn polynomial_norm_test() {
+        use crate::constants::parameter_sets::{ParameterSet, KEM_512, KEM_768};
+
+        // CBD_eta has mean 0 and variance eta/2. Over N coefficients the
+        // sample mean has variance sigma²/N and the sample variance has
+        // variance (mu_4 - sigma⁴)/N, where mu_4 = 3sigma⁴ - eta/4.
+        // Both must be within 3 sigma (compared squared, no sqrt in core).
+        fn check<Eta: typenum::Unsigned>() {
+            let eta = Eta::USIZE as f64;
+            let polys = 40; // 10240 coefficients
+            let count = (polys * n) as f64;
+
+            let (mut sum, mut sum_sq) = (0f64, 0f64);
+            for i in 0..polys {
+                let r = RingElement::sample_poly_cbd::<Eta>(&[0x5A; 32], i as u8);
+                for c in r.coefs.iter() {
+                    let c = f64::from(c.centered());
+                    sum += c;
+                    sum_sq += c * c;
+                }
+            }
+            let mean = sum / count;
+            let var = sum_sq / count - mean * mean;
+
+            let sigma2 = eta / 2.0;
+            let mu4 = 3.0 * sigma2 * sigma2 - eta / 4.0;
+            assert!(
+                mean * mean < 9.0 * sigma2 / count,
+                "eta = {eta}: mean {mean}"
+            );
+            let dev = var - sigma2;
+            assert!(
+                dev * dev < 9.0 * (mu4 - sigma2 * sigma2) / count,
+                "eta = {eta}: variance {var}, expected {sigma2}"
+            );
+        }
+        check::<<KEM_512 as ParameterSet>::EtaOne>();
+        check::<<KEM_768 as ParameterSet>::EtaOne>();
+    }
+
+    #[test]
+    fn cbd_eta2_frequencies_pass_chi_squared() {
+        let samples: alloc::vec::Vec<RingElement> = (0..1000)
+            .map(|i| {
+                let seed = [(i & 0xFF) as u8; 32];
+                RingElement::sample_poly_cbd::<typenum::U2>(&seed, (i >> 8) as u8)
+            })
+            .collect();
+        let hist = RingElement::coefficient_frequency_distribution(&samples);
+        assert_eq!(
+            hist.iter().map(|&(v, _)| v).collect::<alloc::vec::Vec<_>>(),
+            [-2, -1, 0, 1, 2]
+        );
+
+        // P(v) = C(4, v + 2) / 16 for CBD_2. With 4 degrees of freedom the
+        // 99% critical value of chi² is 13.277.
+        let total = (samples.len() * n) as f64;
+        let chi2: f64 = hist
+            .iter()
+            .zip([1.0, 4.0, 6.0, 4.0, 1.0])
+            .map(|(&(_, count), weight)| {
+                let expected = total * weight / 16.0;
+                let d = count as f64 - expected;
+                d * d / expected
+            })
+            .sum();
+        assert!(chi2 < 13.277, "chi² = {chi2}");
+    }
+
+    #[test]
+    fn cbd_eta3_is_in_range_and_not_degenerate() {
+        let r = RingElement::sample_poly_cbd_eta3(&[5u8; 32], 0);
+        assert!(r.infinity_norm() <= 3);
+        assert!(r.coefs.iter().any(|c| c.centered() == 3));
+        assert!(r.coefs.iter().any(|c| c.centered() == -3));
+    }
+
+    #[test]
+    fn cbd_infinity_norm_is_bounded_by_eta() {
+        for b in 0..64 {
+            let r = RingElement::sample_poly_cbd::<typenum::U2>(&[b; 32], b);
+            assert!(r.infinity_norm() <= 2);
+            let r = RingElement::sample_poly_cbd::<typenum::U3>(&[b; 32], b);
+            assert!(r.infinity_norm() <= 3);
+        }
+    }
+
+    #[test]
+    fn infinity_norm_of_signed_element() {
+        let mut vals = [0i16; n];
+        vals[7] = -5;
+        vals[9] = 4;
+        assert_eq!(
+            RingElement::from_signed_coefficients(vals).infinity_norm(),
+            5
+        );
+        assert_eq!(RingElement::zero().infinity_norm(), 0);
+    }
+
+    #[test]
+    fn cbd_l2_norm_matches_distribution() {
+        // For CBD_eta each coefficient has E[c²] = eta/2 and
+        // Var[c²] = E[c⁴] - E[c²]² = eta/2 + 3eta(eta-1)/4 - eta²/4.
+        // Check the sample mean of ||r||² over many polynomials is within
+        // 5 sigma of 256 * eta/2.
+        fn check<Eta: typenum::Unsigned>() {
+            let eta = Eta::USIZE as f64;
+            let samples = 1000;
+            let mean = n as f64 * eta / 2.0;
+            let var_c2 = eta / 2.0 + 3.0 * eta * (eta - 1.0) / 4.0 - eta * eta / 4.0;
+            let var_mean = n as f64 * var_c2 / samples as f64;
+
+            let mut total = 0u64;
+            for i in 0..samples {
+                let seed = [(i & 0xFF) as u8; 32];
+                let r = RingElement::sample_poly_cbd::<Eta>(&seed, (i >> 8) as u8);
+                total += r.l2_norm_squared();
+            }
+            let avg = total as f64 / samples as f64;
+            let dev = avg - mean;
+            assert!(
+                dev * dev < 25.0 * var_mean,
+                "eta = {eta}: mean {avg}, expected {mean}"
+            );
+        }
+        check::<typenum::U2>();
+        check::<typenum::U3>();
+    }
+
+    #[test]
+    fn l2_norm_of_signed_element() {
+        let mut vals = [0i16; n];
+        vals[0] = -3;
+        vals[1] = 4;
+        assert_eq!(
+            RingElement::from_signed_coefficients(vals).l2_norm_squared(),
+            25
+        );
+    }
+
+    #[test]
+    fn scalar_product_with_zero_and_range() {
+        let a = RingElement::sample_poly_cbd::<typenum::U3>(&[9u8; 32], 0);
+        let b = RingElement::sample_poly_cbd::<typenum::U2>(&[9u8; 32], 1);
+        assert_eq!(a.scalar_product(RingElement::zero()).val(), 0);
+        assert!(a.scalar_product(b).val() < q);
+
+        // all-ones against itself is n
+        let ones = RingElement::new([F::ONE; n]);
+        assert_eq!(ones.scalar_product(ones).val(), n as u16);
+    }
+
+    #[test]
+    fn convolution_wraps_with_negation() {
+        // X^255 * X = X^256 = -1
+        let monomial = |i: usize| {
+            let mut vals = [0u16; n];
+            vals[i] = 1;
+            RingElement::from_u16_array(vals)
+        };
+
+        let mut expected = [0u16; n];
+        expected[0] = q - 1;
+        assert_eq!(
+            monomial(n - 1).convolution_mul(monomial(1)).to_u16_array(),
+            expected
+        );
+    }
+
+    #[test]
+    fn convolution_matches_ntt_for_small_monomials() {
+        // every monomial c * X^i for small c against a fixed CBD sample
+        let b = RingElement::sample_poly_cbd::<typenum::U2>(&[7u8; 32], 0);
+        for i in 0..n {
+            for c in 1..=3 {
+                let mut a = RingElement::zero();
+                a.coefs[i] = F::new(c);
+                assert_eq!(
+                    a.convolution_mul(b),
+                    ntt_mul(a, b),
+                    "mismatch at i = {i}, c = {c}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn convolution_matches_ntt_for_cbd_samples() {
+        for b in 0..16 {
+            let x = RingElement::sample_poly_cbd::<typenum::U3>(&[1u8; 32], b);
+            let y = RingElement::sample_poly_cbd::<typenum::U2>(&[2u8; 32], b);
+            assert_eq!(x.convolution_mul(y), ntt_mul(x, y));
+        }
+    }
+
+    fn random_element(rng: &mut impl rand::Rng) -> RingElement {
+        RingElement::new(core::array::from_fn(|_| F::new(rng.gen_range(0..q))))
+    }
+
+    #[test]
+    fn ring_mul_matches_convolution() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0x1155);
+        for _ in 0..16 {
+            let a = random_element(&mut rng);
+            let b = random_element(&mut rng);
+            assert_eq!(a * b, a.convolution_mul(b));
+        }
+    }
+
+    #[test]
+    fn ring_mul_is_associative() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0x1155_0001);
+        for _ in 0..16 {
+            let a = random_element(&mut rng);
+            let b = random_element(&mut rng);
+            let c = random_element(&mut rng);
+            assert_eq!((a * b) * c, a * (b * c));
+        }
+    }
+
+    #[test]
+    fn scalar_mul_by_one_and_zero() {
+        for b in 0..4 {
+            let r = RingElement::sample_poly_cbd_eta3(&[b; 32], b);
+            assert_eq!(r * F::ONE, r);
+            assert!((r * F::ZERO).is_zero());
+
+            let mut s = r;
+            s *= F::new(2);
+            assert_eq!(s, r + r);
+        }
+    }
+
+    #[test]
+    fn ct_eq_matches_eq() {
+        use subtle::ConstantTimeEq;
+
+        let a = RingElement::sample_poly_cbd_eta2(&[1u8; 32], 0);
+        let mut b = a;
+        assert!(bool::from(a.ct_eq(&b)));
+
+        b.coefs[n - 1] += F::ONE;
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert!(!bool::from(
+            a.ct_eq(&RingElement::sample_poly_cbd_eta2(&[1u8; 32], 1))
+        ));
+    }
+
+    #[test]
+    fn map_coefficients_matches_inplace() {
+        let r = RingElement::sample_poly_cbd_eta2(&[8u8; 32], 8);
+        let doubled = r.map_coefficients(|c| c + c);
+        assert_eq!(doubled, r + r);
+
+        let mut s = r;
+        s.map_coefficients_inplace(|c| c + c);
+        assert_eq!(s, doubled);
+        assert_eq!(r.map_coefficients(|_| F::zero()), RingElement::zero());
+    }
+
+    #[test]
+    fn compress_copy_leaves_original() {
+        use crate::math::encoding::Compress;
+        use typenum::U10;
+
+        let r = RingElement::sample_poly_cbd_eta2(&[6u8; 32], 6);
+        let c = r.compress_copy::<U10>();
+        let mut expected = r;
+        expected.compress::<U10>();
+        assert_eq!(c, expected);
+        assert_ne!(c, r);
+
+        let d = c.decompress_copy::<U10>();
+        expected.decompress::<U10>();
+        assert_eq!(d, expected);
+        assert_eq!(c, r.compress_copy::<U10>());
+    }
+
+    #[test]
+    fn message_roundtrip() {
+        use super::{message_to_ring_element, ring_element_to_message};
+
+        for seed in 0..8u8 {
+            let m: [u8; 32] = core::array::from_fn(|i| (i as u8).wrapping_mul(91) ^ seed);
+            let r = message_to_ring_element(&m);
+            assert!(r.coefs.iter().all(|c| c.val() == 0 || c.val() == 1665));
+            assert_eq!(ring_element_to_message(&r), m);
+        }
+    }
+
+    #[test]
+    fn hamming_weight_counts_message_bits() {
+        use crate::math::encoding::{Compress, Encode};
+        use typenum::U1;
+
+        for seed in 0..8u8 {
+            let m: [u8; 32] = core::array::from_fn(|i| (i as u8).wrapping_mul(37) ^ seed);
+            let mut mu: RingElement = Encode::<U1>::decode(&m);
+            mu.decompress::<U1>();
+
+            let bits: u32 = m.iter().map(|b| b.count_ones()).sum();
+            assert_eq!(mu.hamming_weight(), bits);
+        }
+        assert_eq!(RingElement::zero().hamming_weight(), 0);
+    }
+
+    #[test]
+    fn zeroizing_clone_is_equal_and_wipes() {
+        use crate::math::util::ZeroizingClone;
+        use zeroize::Zeroize;
+
+        let r = RingElement::sample_poly_cbd_eta2(&[4u8; 32], 4);
+        let mut copy = r.zeroizing_clone();
+        assert_eq!(*copy, r);
+
+        // `Zeroizing` calls exactly this on drop
+        copy.zeroize();
+        assert!(copy.is_zero());
+        assert!(!r.is_zero());
+    }
+}
@@ -1,3 +1,35 @@
+use super::field_element::FieldElement;
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Narrowing conversion into `T` that is aware of what `T` represents.
+pub(crate) trait Truncate<T> {
+    fn truncate(self) -> T;
+}
+
+/// Reduces mod q before narrowing, so the upper bits of a product are never
+/// dropped. Valid for any `u32` below `q²`, which covers the product of two
+/// reduced field elements.
+impl Truncate<FieldElement> for u32 {
+    fn truncate(self) -> FieldElement {
+        FieldElement::barrett_reduce(self)
+    }
+}
+
+/// Cloning for secret values: the copy comes back inside [`Zeroizing`], so
+/// it is wiped when dropped just like the original should be.
+pub(crate) trait ZeroizingClone: Zeroize + Sized {
+    fn zeroizing_clone(&self) -> Zeroizing<Self>;
+}
+
+/// Returns true iff `a` and `b` have the same length and contents.
+///
+/// The contents are compared in constant time; only the length check
+/// (which is public information here) may short-circuit.
+pub(crate) fn ct_bytes_equal(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.ct_eq(b).unwrap_u8() == 1
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -33,6 +65,27 @@ mod tests {
         result as u16
     }
 
+    #[test]
+    fn ct_bytes_equal_handles_lengths() {
+        use super::ct_bytes_equal;
+
+        assert!(ct_bytes_equal(&[1, 2, 3], &[1, 2, 3]));
+        assert!(ct_bytes_equal(&[], &[]));
+        assert!(!ct_bytes_equal(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!ct_bytes_equal(&[1, 2, 3], &[1, 2]));
+    }
+
+    #[test]
+    fn truncate_to_field_element_reduces_first() {
+        use super::Truncate;
+        use crate::math::field_element::FieldElement;
+
+        for x in [0u32, 1, 3329, 0x1_0000, 3328 * 3328] {
+            let f: FieldElement = x.truncate();
+            assert_eq!(u32::from(f.val()), x % u32::from(Q));
+        }
+    }
+
     #[test]
     fn create_and_test_kntt_roots() {
         let kntt_roots: Vec<u16> = (0..128)
@@ -0,0 +1,262 @@
+//! A small streaming wrapper around the SHA-3 XOFs used by the samplers.
+//!
+//! The samplers only need "absorb some bytes, then squeeze some bytes", so
+//! they take any [`XofReader`] and leave the choice of hash to the caller.
+//!
+//! ## Non-FIPS XOFs
+//!
+//! <div class="warning">
+//!
+//! **The `blake3-xof` feature is not FIPS 203 compliant.** With it enabled,
+//! [`XofStream128`] and [`XofStream256`] are backed by BLAKE3 instead of
+//! SHAKE-128 and SHAKE-256, so `XOF` and `PRF` (and everything sampled from
+//! them) change. Keys and ciphertexts produced this way interoperate only
+//! with other builds that enable the same feature. `G`, `H` and `J` stay
+//! SHA-3 either way.
+//!
+//! BLAKE3 targets 128-bit security against all attacks. That matches
+//! ML-KEM-512, but is below what ML-KEM-768 and ML-KEM-1024 require, so with
+//! the feature enabled those parameter sets fail to compile and
+//! `AnyKem` only offers `MlKem512`. Do not enable the feature where FIPS
+//! validation is required.
+//!
+//! </div>
+//!
+//! `XOF` and `PRF` are kept apart by running BLAKE3 in key derivation mode
+//! with a different context string for each, where SHA-3 uses two
+//! different functions.
+
+use sha3::digest::{ExtendableOutput, XofReader};
+#[cfg(not(feature = "blake3-xof"))]
+use sha3::{Shake128, Shake256};
+
+/// An XOF that can be fed with [`XofStream::update`] and then read from.
+///
+/// The first call to [`XofStream::read`] finalizes the absorb phase; any
+/// `update` after that is a logic error and panics.
+pub struct XofStream<X: ExtendableOutput> {
+    hasher: Option<X>,
+    reader: Option<X::Reader>,
+}
+
+/// `SHAKE-128`, used for `XOF` (sampling the matrix `A`).
+#[cfg(not(feature = "blake3-xof"))]
+pub type XofStream128 = XofStream<Shake128>;
+
+/// `SHAKE-256`, used for `PRF` (sampling noise).
+#[cfg(not(feature = "blake3-xof"))]
+pub type XofStream256 = XofStream<Shake256>;
+
+/// BLAKE3 standing in for `SHAKE-128` as `XOF`. Not FIPS 203 compliant.
+#[cfg(feature = "blake3-xof")]
+pub type XofStream128 = XofStream<blake3_backend::Blake3<blake3_backend::XofContext>>;
+
+/// BLAKE3 standing in for `SHAKE-256` as `PRF`. Not FIPS 203 compliant.
+#[cfg(feature = "blake3-xof")]
+pub type XofStream256 = XofStream<blake3_backend::Blake3<blake3_backend::PrfContext>>;
+
+impl<X: ExtendableOutput + Default> XofStream<X> {
+    pub fn new() -> Self {
+        XofStream {
+            hasher: Some(X::default()),
+            reader: None,
+        }
+    }
+}
+
+impl<X: ExtendableOutput + Default> Default for XofStream<X> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<X: ExtendableOutput> XofStream<X> {
+    pub fn update(&mut self, data: &[u8]) {
+        self.hasher
+            .as_mut()
+            .expect("XofStream::update called after read")
+            .update(data);
+    }
+
+    /// Ends the absorb phase and returns the underlying reader. If bytes
+    /// were already read, the reader continues from where they stopped.
+    pub fn finalize_xof(mut self) -> X::Reader {
+        match self.hasher.take() {
+            Some(hasher) => hasher.finalize_xof(),
+            None => self.reader.take().expect("XofStream has no state"),
+        }
+    }
+
+    pub fn read(&mut self, buf: &mut [u8]) {
+        if let Some(hasher) = self.hasher.take() {
+            self.reader = Some(hasher.finalize_xof());
+        }
+        self.reader
+            .as_mut()
+            .expect("XofStream has no state")
+            .read(buf);
+    }
+}
+
+impl<X> Clone for XofStream<X>
+where
+    X: ExtendableOutput + Clone,
+    X::Reader: Clone,
+{
+    fn clone(&self) -> Self {
+        XofStream {
+            hasher: self.hasher.clone(),
+            reader: self.reader.clone(),
+        }
+    }
+}
+
+impl<X: ExtendableOutput> XofReader for XofStream<X> {
+    fn read(&mut self, buf: &mut [u8]) {
+        XofStream::read(self, buf);
+    }
+}
+
+#[cfg(feature = "blake3-xof")]
+mod blake3_backend {
+    use core::marker::PhantomData;
+    use sha3::digest::{ExtendableOutput, Update, XofReader};
+
+    /// The BLAKE3 key derivation context that separates one use of the
+    /// hash from the other.
+    pub trait Context {
+        const CONTEXT: &'static str;
+    }
+
+    #[derive(Clone)]
+    pub struct XofContext;
+
+    impl Context for XofContext {
+        const CONTEXT: &'static str = "capyKEM 2024-06 non-FIPS XOF";
+    }
+
+    #[derive(Clone)]
+    pub struct PrfContext;
+
+    impl Context for PrfContext {
+        const CONTEXT: &'static str = "capyKEM 2024-06 non-FIPS PRF";
+    }
+
+    /// `blake3::Hasher` in key derivation mode for context `C`, behind the
+    /// `digest` traits that [`XofStream`](super::XofStream) is written
+    /// against.
+    #[derive(Clone)]
+    pub struct Blake3<C> {
+        hasher: blake3::Hasher,
+        _context: PhantomData<C>,
+    }
+
+    impl<C: Context> Default for Blake3<C> {
+        fn default() -> Self {
+            Blake3 {
+                hasher: blake3::Hasher::new_derive_key(C::CONTEXT),
+                _context: PhantomData,
+            }
+        }
+    }
+
+    impl<C> Update for Blake3<C> {
+        fn update(&mut self, data: &[u8]) {
+            self.hasher.update(data);
+        }
+    }
+
+    impl<C> ExtendableOutput for Blake3<C> {
+        type Reader = Blake3Reader;
+
+        fn finalize_xof(self) -> Blake3Reader {
+            Blake3Reader(self.hasher.finalize_xof())
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct Blake3Reader(blake3::OutputReader);
+
+    impl XofReader for Blake3Reader {
+        fn read(&mut self, buffer: &mut [u8]) {
+            self.0.fill(buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{XofStream128, XofStream256};
+    use sha3::digest::XofReader;
+
+    #[test]
+    #[cfg_attr(feature = "blake3-xof", ignore = "pinned to SHAKE output")]
+    fn matches_shake128() {
+        use sha3::{
+            digest::{ExtendableOutput, Update},
+            Shake128,
+        };
+
+        let mut expected = [0u8; 200];
+        let mut hasher = Shake128::default();
+        hasher.update(b"abc");
+        hasher.update(b"def");
+        hasher.finalize_xof().read(&mut expected);
+
+        let mut stream = XofStream128::new();
+        stream.update(b"abc");
+        stream.update(b"def");
+        let mut out = [0u8; 200];
+        stream.read(&mut out[..50]);
+        stream.finalize_xof().read(&mut out[50..]);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "blake3-xof")]
+    fn blake3_streams_match_derive_key_mode() {
+        use super::blake3_backend::{Context, PrfContext, XofContext};
+
+        let mut expected = [0u8; 200];
+        let mut hasher = blake3::Hasher::new_derive_key(XofContext::CONTEXT);
+        hasher.update(b"abcdef");
+        hasher.finalize_xof().fill(&mut expected);
+
+        let mut stream = XofStream128::new();
+        stream.update(b"abc");
+        stream.update(b"def");
+        let mut out = [0u8; 200];
+        stream.read(&mut out[..50]);
+        stream.finalize_xof().read(&mut out[50..]);
+        assert_eq!(out, expected);
+
+        // XOF and PRF must not collide on the same input.
+        let mut prf = [0u8; 200];
+        let mut stream = XofStream256::new();
+        stream.update(b"abcdef");
+        stream.read(&mut prf);
+        assert_ne!(prf, expected);
+        assert_ne!(XofContext::CONTEXT, PrfContext::CONTEXT);
+    }
+
+    #[test]
+    fn clone_before_read_forks_the_stream() {
+        let mut stream = XofStream128::new();
+        stream.update(b"prefix");
+        let mut fork = stream.clone();
+        stream.update(b"a");
+        fork.update(b"a");
+        let (mut x, mut y) = ([0u8; 64], [0u8; 64]);
+        stream.read(&mut x);
+        fork.read(&mut y);
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    #[should_panic]
+    fn update_after_read_panics() {
+        let mut stream = XofStream256::new();
+        stream.read(&mut [0u8; 1]);
+        stream.update(b"late");
+    }
+}
@@ -0,0 +1,49 @@
+//! The non-FIPS `blake3-xof` feature, which only supports ML-KEM-512.
+
+#[cfg(all(test, feature = "blake3-xof"))]
+mod tests {
+    use capy_kem::{
+        constants::parameter_sets::KEM_512,
+        fips203::{
+            decrypt::mlkem_decaps,
+            encrypt::mlkem_encaps,
+            kem::{make_kem, AnyKem},
+            keygen::{keygen_from_prf_output, ml_kem_keygen},
+        },
+    };
+    use rand::thread_rng;
+    use sha3::{Digest, Sha3_256};
+    use subtle::ConstantTimeEq;
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn roundtrip_512() {
+        let mut rng = thread_rng();
+        let (pk, sk) = ml_kem_keygen::<KEM_512, _>(&mut rng).unwrap();
+        let (K, mut c) = mlkem_encaps::<KEM_512, _>(&pk.ek, &mut rng).unwrap();
+        assert!(bool::from(
+            mlkem_decaps::<KEM_512>(&c, &sk.dk).unwrap().ct_eq(&K)
+        ));
+
+        c[0] ^= 1;
+        assert!(!bool::from(
+            mlkem_decaps::<KEM_512>(&c, &sk.dk).unwrap().ct_eq(&K)
+        ));
+    }
+
+    #[test]
+    fn blake3_is_in_use() {
+        // The FIPS 203 key for this seed starts e5bd1b37.
+        let (pk, _) = keygen_from_prf_output::<KEM_512>(&[0u8; 64]);
+        assert_ne!(Sha3_256::digest(&pk.ek)[..4], [0xe5, 0xbd, 0x1b, 0x37]);
+    }
+
+    #[test]
+    fn dyn_kem_roundtrip() {
+        let mut rng = thread_rng();
+        let kem = make_kem(AnyKem::MlKem512);
+        let (ek, dk) = kem.keygen(&mut rng).unwrap();
+        let (k, c) = kem.encaps(&ek, &mut rng).unwrap();
+        assert!(bool::from(kem.decaps(&dk, &c).unwrap().ct_eq(&k)));
+    }
+}
@@ -0,0 +1,94 @@
+//! Decapsulation must not touch the heap, see `mlkem_decaps_into`.
+//!
+//! This binary installs a global allocator that counts allocations per
+//! thread, so tests running in parallel do not disturb each other.
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "blake3-xof"))]
+    use capy_kem::constants::parameter_sets::{KEM_1024, KEM_768};
+    use capy_kem::{
+        constants::parameter_sets::{ParameterSet, KEM_512},
+        fips203::{
+            decrypt::{mlkem_decaps, mlkem_decaps_into},
+            encrypt::mlkem_encaps,
+            keygen::keygen_from_prf_output,
+        },
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+    };
+
+    struct CountingAlloc;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAlloc = CountingAlloc;
+
+    fn allocations_during(f: impl FnOnce()) -> usize {
+        let before = ALLOCATIONS.with(Cell::get);
+        f();
+        ALLOCATIONS.with(Cell::get) - before
+    }
+
+    fn check<P: ParameterSet>() {
+        let (pk, sk) = keygen_from_prf_output::<P>(&[0x42; 64]);
+        let mut rng = StdRng::seed_from_u64(1185);
+        let (k, c) = mlkem_encaps::<P, _>(&pk.ek, &mut rng).unwrap();
+        let mut tampered = c.clone();
+        tampered[0] ^= 1;
+
+        let mut out = [0u8; 32];
+        let n = allocations_during(|| {
+            mlkem_decaps_into::<P>(&c, &sk.dk, &mut out).unwrap();
+        });
+        assert_eq!(n, 0, "{}: decaps_into allocated", P::LABEL);
+        assert_eq!(&out, k.as_bytes());
+
+        // implicit rejection takes the same path
+        let n = allocations_during(|| {
+            mlkem_decaps_into::<P>(&tampered, &sk.dk, &mut out).unwrap();
+        });
+        assert_eq!(n, 0, "{}: rejecting decaps_into allocated", P::LABEL);
+        assert_ne!(&out, k.as_bytes());
+
+        let mut k_prime = None;
+        let n = allocations_during(|| {
+            k_prime = Some(mlkem_decaps::<P>(&c, &sk.dk).unwrap());
+        });
+        assert_eq!(n, 0, "{}: decaps allocated", P::LABEL);
+        assert_eq!(k_prime.unwrap().as_bytes(), k.as_bytes());
+
+        // the counter does see allocations: the ciphertext is a Vec
+        let n = allocations_during(|| {
+            let _ = mlkem_encaps::<P, _>(&pk.ek, &mut rng).unwrap();
+        });
+        assert!(n > 0);
+    }
+
+    #[test]
+    fn decaps_does_not_allocate() {
+        check::<KEM_512>();
+        #[cfg(not(feature = "blake3-xof"))]
+        {
+            check::<KEM_768>();
+            check::<KEM_1024>();
+        }
+    }
+}
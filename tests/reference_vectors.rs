@@ -0,0 +1,253 @@
+//! Known-answer tests against FIPS 203 ML-KEM.
+//!
+//! Each vector is a `(d || z, m)` input pair together with the expected
+//! `ek`, `dk`, `K` and `c`. `K` is compared directly; `ek`, `dk` and `c`
+//! are compared through their SHA3-256 digests to keep this file readable.
+//! `k_bar` is the implicit rejection key `J(z || c')` for `c'` equal to `c`
+//! with its lowest bit flipped.
+//!
+//! The expected values were produced with the ML-KEM implementation in
+//! OpenSSL 3.5 (`genpkey -pkeyopt hexseed:`, `pkeyutl -encap -pkeyopt
+//! hexikme:`). ML-KEM is deterministic given `d`, `z` and `m`, so every
+//! conforming implementation, including pq-crystals and mlkem-native,
+//! produces the same bytes. The inputs are `SHAKE-128("capyKEM KAT seed i")`
+//! and `SHAKE-128("capyKEM KAT msg i")`.
+//!
+//! No test in this file may use system randomness. The vectors assume
+//! SHAKE, so nothing here runs with the `blake3-xof` feature.
+
+#[cfg(all(test, not(feature = "blake3-xof")))]
+mod tests {
+    use capy_kem::{
+        constants::parameter_sets::{ParameterSet, KEM_1024, KEM_512, KEM_768},
+        fips203::{
+            decrypt::mlkem_decaps,
+            encrypt::mlkem_encaps,
+            keygen::{keygen_from_prf_output, ml_kem_keygen},
+        },
+    };
+    use rand::{rngs::StdRng, CryptoRng, Error, RngCore, SeedableRng};
+    use sha3::{Digest, Sha3_256};
+    use subtle::ConstantTimeEq;
+
+    struct Vector {
+        seed: &'static str,
+        m: &'static str,
+        ek_sha3: &'static str,
+        dk_sha3: &'static str,
+        k: &'static str,
+        c_sha3: &'static str,
+        k_bar: &'static str,
+    }
+
+    const VECTORS_512: &[Vector] = &[
+        Vector {
+            seed: "10c4ed4dac08269b2922141149418eb8a90e860cb41548285042a135895cfabb13315d15bcf16d9b014a5b9a579506a17cfb0fec92020ca854ae0c449def28d3",
+            m: "dbe03a49a8e82d9effc4af29f4a1cd0e654bac1adaf1141a92ec405744b8c854",
+            ek_sha3: "d90f4feccd40deafe2cd409d1a63fdb88cc460825dd865e2e51aecf1b167ff72",
+            dk_sha3: "340d73e519a743d1747bf0dccfd45651ec6a45f05abc370490406b5c6ed31541",
+            k: "47e5201ce9d4bd986b3c83a95a0bc9f8bd81dd41032653f62af4c375193ac42a",
+            c_sha3: "1643b55ce20decc6cebc5c30131b8fdea9974ecf1edcc979e5c00addea04f8b8",
+            k_bar: "13037db09b75d9cdba905d399bc4a18122a8d095a23ecee05c3e930986892da2",
+        },
+        Vector {
+            seed: "e061e9cd8b4296aed9bff51e68ff7a6988f01ecb58a82adb5da542615f9ca2578e135d4cbed8a8ac4551a993d05d1a953d094589ae54143665fac9aa813777d2",
+            m: "5d0a76fc6c026d22d519e89ab45ed3007bdb00724eed731e96ebb378ba0614d9",
+            ek_sha3: "faf6925e71bb2ffcc19d554683c380fcfebe6279ac2f7f0d25836ff7cf9a8cb9",
+            dk_sha3: "9184cc4a347a2c60ea212201338fd5479fd70e3b24da8de12249e2775f2fec4e",
+            k: "c5811da73e653817f91036f9f55da4736ba796339203645b88b14bc4c1b153f4",
+            c_sha3: "491e169b028a8fdf217e12638ec71b8f3ddcf3519778566672820418cdf57b1d",
+            k_bar: "d92ee58cf07a08436fb9dcaca43c51f4a75e4b56feb7a8ab01fbe4443e7f6540",
+        },
+        Vector {
+            seed: "f5db87d13e2c4ede1bdf8aa321de2f71e7f40b34ddafeb6540e3b39df8e962de403386d6b4b39bcd31f33de1fffc92716aa59524afa3a0a1043ae6e79b698ef2",
+            m: "981a2afc75be2f2e6ea883f95f74ee200dadf0418c1795a8cdccffc836bd0adf",
+            ek_sha3: "e6e25584cde2919741095411a862080ffdbda0183c99ffb78656b7b4ce96e29a",
+            dk_sha3: "42f53717f96fe22fb9613728bfa2d6a37475cd80c2cab4316924defaabae2d2a",
+            k: "4993996f163088aaa1eb362dab27fe850d2135176f7d28f10567e98e7ee4dc58",
+            c_sha3: "87b191f75df9cd7ab3008342f4daef59c7c4c53220e1ca7d51261ea11e1c5aa3",
+            k_bar: "2cf4ff9d56a0b8babcd44b598d3d7184c33f9244e967c4674fe539e5969a8dba",
+        },
+        Vector {
+            seed: "bd5d7ee36423375f3db2ca70d4d02e692a85610c13f0effe9e7d8cb6bfaff7a40b43254ca23d2d1119098563c7c671e3829aa2554eaef5bffb664b878151bb15",
+            m: "7043998aaa876a8a9c75b61d84ef9a4efce881804fe2705352f1be33bd9e2e98",
+            ek_sha3: "6d499442f2af013c788139a506e02bcba9c0852c48aa2343f1d0ea44fd52b03f",
+            dk_sha3: "dc35592927e902cfdcf71aac9180c0a4d437cc639f1c032dbaa116ffe0c11e66",
+            k: "231fa865b85d4823648d48820eef07efd8242f5a51a050993d9f2b80993ee3f3",
+            c_sha3: "bc28cbcb8b3f28f0440ead2fb0d38dccaa09bc162255296ddddfa58d42c8b7d9",
+            k_bar: "cb27c8a9692df3a825d087c288a4b096bb55ba089910afa072341a864207a998",
+        },
+    ];
+
+    const VECTORS_768: &[Vector] = &[
+        Vector {
+            seed: "67b50d996f028eeb50e78d0a93e0fdc9515069313e8e9663edef73a3bef600be24e78a908a1590ba46cad8f67303dae8c7d851d61b162c450c3555be818fb153",
+            m: "f9b2af3706a9e257455e45bbe4b4648ee023f77fea60b46ea3899d72e48f1934",
+            ek_sha3: "dc99aec7e602362960aa74ab3893fe9690bb6a02dad4b2b0a9e58a2cbd71047e",
+            dk_sha3: "09d41c412aaae61ee9efbe942124e380e9e215c7cb7d905c029733c983b1cf3e",
+            k: "095d78390958c9237d0f5566d3887fab49b982a07335be585fcd1fc6ca735bf2",
+            c_sha3: "7748e6bb88f07d62d3b63f5aaa5a046c1613fce68730312b5bf081f4cb9d0c51",
+            k_bar: "207d437a134cd2f1e1be3bd5a7104a39757001849776c39f56ae8de8c11fb2ff",
+        },
+        Vector {
+            seed: "b9550604faccd1613f9c68d12924cecfe840582b981a3933a96b4a81ea5425ca0f0e36ac29a7fe5e864c01bdd1340490080872eb58f6d66b184d419b4326de15",
+            m: "678839a7d2384dbc07d72032afe7c974d9b439886fb9a87c7d1eb2773174dace",
+            ek_sha3: "8fc4d92442eb70de9ea9a6a5707f32bdb9d3c02d943d93505bdf06db7f4ebe6d",
+            dk_sha3: "406db64aad821f5198c570de12fd1051ff81b222acdc151e92872c8d71a9f170",
+            k: "2995b6c78d566b212a5aa039c2bbc08193b84888432b70e6850430cd13f022b8",
+            c_sha3: "715dcd628a100851bc03bbe354157ae001ac781c30d49d7bb0b3844afeeb4797",
+            k_bar: "edd3995ec1efaf93796bec59078268efb12ee819528965ed58693c4d111d5777",
+        },
+        Vector {
+            seed: "fe828fb04ccf100ed666dbb3167a875e4ac69236f0339c8d331e834bd64cd8720d44ec18710f79fb9fe781ecbb7d6d53edb3ad9de7f1f385ca9eda42f6c427e4",
+            m: "706bd6608da038349874f8cb2359f33f9c743b51c33d67485d9a749316455d41",
+            ek_sha3: "c8e69f57f13d593f13361f85090b63f60fcd032871e39b604da5ad9fac3202cc",
+            dk_sha3: "d61efbed78e8dd093ad351bf6f1801d08a152cfd24aee2848c765b6d3797b68e",
+            k: "18147b27b536fc7256862266dd7ba75b037c9f893336a8b49cd04b2d197dd507",
+            c_sha3: "4d48a587f38b497fad36f2f0c5455b8ecb4db94a99543ddf2778d26b777b2fdc",
+            k_bar: "c5ba193d0d7f4952504d01403c77cefefa5ee8a451473d7602053829c908912a",
+        },
+    ];
+
+    const VECTORS_1024: &[Vector] = &[
+        Vector {
+            seed: "e14d921da13505f9215cafa021fe50ae6825428fa8da4dd2dcdbd5e7e0e050a2a24f44844c89444647a92b7f9eec2ce47b57468b84d2e32c47d2abe917ff21de",
+            m: "4345a80a17fccb4d06a22e91feb09e3c6c47789756c18d85a9da7def554d73af",
+            ek_sha3: "2afa68f60798c0e80c6601f8af6f3dbb11f7c0731765c0e9ebf0f188aa295f3c",
+            dk_sha3: "1841df052f6dfbdd0700a71b4046724e7c0a4f86ca279e163dd1e70f6a0bc67c",
+            k: "d234fadee0f0e847266f886b2e9dc2c2647ecbd68b48c43c5011709669fabc09",
+            c_sha3: "17dc3ff182c2d6c1a6870c389dc3b886f372afcaace7d0e1afbfcd640ad45395",
+            k_bar: "acb360c5669edaeff96c70a2f1b92acb167388f4ea63ea0ee4803d8331b56ad9",
+        },
+        Vector {
+            seed: "cfea3b0b8504b50fe0faab405488c8af7ecfbc38b2b0aae8f30b513447a230a939b123cfb485a3f574d955e4970a1f552693a128d849bb6a5c38ca9c24ad9d44",
+            m: "a27f2c4baed4d60926f8e494016dc5f444bb7d56faf9583491072bf88af888bb",
+            ek_sha3: "e48e967eb7e7802042e96c5de1ec5bbc3c566dbb3a0f780800d9e9dd2e483d67",
+            dk_sha3: "21814c24d928b352dcb052d54f561bcd3d373642bcc16e75c2d11826121084bb",
+            k: "acd765995a43e073079730f02f62ecb11765faae76ca245eb85ce942584e5816",
+            c_sha3: "7c50faa02a233259993d0df7647f94c0e8553fc30ed02839625bfb534ebc2d76",
+            k_bar: "00fb662033ce843daa2454152e9119baccfbf656d231391c00cc942b601200d4",
+        },
+        Vector {
+            seed: "a86da006ebab3d0387e781d0928c095d5839a4589d947676ec86f4b45558c65cad98580212db23448c681072e811ce5427e50a2d481b2e2dc44876593fc9874d",
+            m: "22c974068b1f80fbeeb20e4e49ed4ddd2b9ac28fbf5835bc48cf3451a2e1b8a0",
+            ek_sha3: "f111ad688cb53d6174cc94cc9cfc7551e11a93f197bfab47655c568abe99e6f4",
+            dk_sha3: "a3d0c9b3a3473c5abcd06952c1a280c1e8e657060f9c60764513749299493689",
+            k: "2f5c71a091683c8cc3d71bcde155a00a97e9ee637b769352854c23125d3137bc",
+            c_sha3: "88160de99d4c239e6e33349cb1df1df48ebbed4c4e93fa7da032fef114b67cb1",
+            k_bar: "0f823df081f53c62a7a7d3a590d61f4314f75574b7fd8b6633aee94685d82259",
+        },
+    ];
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn unhex<const N: usize>(s: &str) -> [u8; N] {
+        assert_eq!(s.len(), 2 * N);
+        core::array::from_fn(|i| u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap())
+    }
+
+    fn sha3(bytes: &[u8]) -> String {
+        hex(&Sha3_256::digest(bytes))
+    }
+
+    // Hands out the fixed encapsulation message `m` as its "random" bytes
+    struct FixedMessage([u8; 32]);
+
+    impl RngCore for FixedMessage {
+        fn next_u32(&mut self) -> u32 {
+            unimplemented!("encapsulation only draws m with fill_bytes")
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            unimplemented!("encapsulation only draws m with fill_bytes")
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.copy_from_slice(&self.0);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for FixedMessage {}
+
+    fn check_vectors<P: ParameterSet>(vectors: &[Vector]) {
+        for (i, v) in vectors.iter().enumerate() {
+            let (pk, sk) = keygen_from_prf_output::<P>(&unhex(v.seed));
+            assert_eq!(sha3(&pk.ek), v.ek_sha3, "{} vector {i}: ek", P::LABEL);
+            assert_eq!(sha3(&sk.dk), v.dk_sha3, "{} vector {i}: dk", P::LABEL);
+
+            let mut m = FixedMessage(unhex(v.m));
+            let (k, mut c) = mlkem_encaps::<P, _>(&pk.ek, &mut m).unwrap();
+            assert_eq!(hex(k.as_bytes()), v.k, "{} vector {i}: K", P::LABEL);
+            assert_eq!(sha3(&c), v.c_sha3, "{} vector {i}: c", P::LABEL);
+
+            let k_prime = mlkem_decaps::<P>(&c, &sk.dk).unwrap();
+            assert_eq!(hex(k_prime.as_bytes()), v.k, "{} vector {i}: K'", P::LABEL);
+
+            c[0] ^= 1;
+            let k_bar = mlkem_decaps::<P>(&c, &sk.dk).unwrap();
+            assert_eq!(
+                hex(k_bar.as_bytes()),
+                v.k_bar,
+                "{} vector {i}: K_bar",
+                P::LABEL
+            );
+        }
+    }
+
+    #[test]
+    fn reference_vectors_512() {
+        check_vectors::<KEM_512>(VECTORS_512);
+    }
+
+    #[test]
+    fn reference_vectors_768() {
+        check_vectors::<KEM_768>(VECTORS_768);
+    }
+
+    #[test]
+    fn reference_vectors_1024() {
+        check_vectors::<KEM_1024>(VECTORS_1024);
+    }
+
+    #[allow(non_snake_case)]
+    fn deterministic_roundtrip<P: ParameterSet>(seed: u64) {
+        let mut rng_a = StdRng::seed_from_u64(seed);
+        let mut rng_b = StdRng::seed_from_u64(seed);
+
+        let (ek_a, dk_a) = ml_kem_keygen::<P, _>(&mut rng_a).unwrap();
+        let (ek_b, dk_b) = ml_kem_keygen::<P, _>(&mut rng_b).unwrap();
+        assert_eq!(ek_a.ek, ek_b.ek, "ek differs for seed {seed}");
+        assert_eq!(dk_a.dk, dk_b.dk, "dk differs for seed {seed}");
+
+        let (K_a, c_a) = mlkem_encaps::<P, _>(&ek_a.ek, &mut rng_a).unwrap();
+        let (K_b, c_b) = mlkem_encaps::<P, _>(&ek_b.ek, &mut rng_b).unwrap();
+        assert!(bool::from(K_a.ct_eq(&K_b)), "K differs for seed {seed}");
+        assert_eq!(c_a, c_b, "c differs for seed {seed}");
+
+        assert!(bool::from(
+            mlkem_decaps::<P>(&c_a, &dk_a.dk).unwrap().ct_eq(&K_a)
+        ));
+    }
+
+    #[test]
+    fn deterministic_512() {
+        (0..10).for_each(deterministic_roundtrip::<KEM_512>);
+    }
+
+    #[test]
+    fn deterministic_768() {
+        (0..10).for_each(deterministic_roundtrip::<KEM_768>);
+    }
+
+    #[test]
+    fn deterministic_1024() {
+        (0..10).for_each(deterministic_roundtrip::<KEM_1024>);
+    }
+}
@@ -1,64 +1,439 @@
-#[cfg(test)]
+// Every test here runs ML-KEM-768 or ML-KEM-1024, which do not build with
+// `blake3-xof`; that feature is covered by `tests/blake3_xof.rs`.
+#[cfg(all(test, not(feature = "blake3-xof")))]
 mod tests {
 
     use capy_kem::{
-        constants::parameter_sets::{KEM_1024, KEM_512, KEM_768},
-        fips203::{decrypt::mlkem_decaps, encrypt::mlkem_encaps, keygen::ml_kem_keygen},
+        constants::parameter_sets::{ParameterSet, KEM_1024, KEM_512, KEM_768},
+        fips203::{
+            decrypt::mlkem_decaps,
+            encrypt::{mlkem_encaps, mlkem_encaps_from_ek},
+            keygen::ml_kem_keygen,
+        },
     };
     use rand::thread_rng;
+    use subtle::ConstantTimeEq;
 
     #[test]
     #[allow(non_snake_case)]
     fn roundtrip_768() {
         let mut rng = thread_rng();
-        let (ek_pke, dk_pke) = ml_kem_keygen::<KEM_768, _>(&mut rng); // Generate key pair
+        let (ek_pke, dk_pke) = ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap(); // Generate key pair
 
         let (K, c) = mlkem_encaps::<KEM_768, _>(&ek_pke.ek, &mut rng).unwrap();
 
         let dec = mlkem_decaps::<KEM_768>(&c, &dk_pke.dk).unwrap();
         // Assert that the decrypted message matches the original message
-        assert_eq!(dec, K);
+        assert!(bool::from(dec.ct_eq(&K)));
     }
 
     #[test]
     #[allow(non_snake_case)]
     fn roundtrip_512() {
         let mut rng = thread_rng();
-        let (ek_pke, dk_pke) = ml_kem_keygen::<KEM_512, _>(&mut rng); // Generate key pair
+        let (ek_pke, dk_pke) = ml_kem_keygen::<KEM_512, _>(&mut rng).unwrap(); // Generate key pair
 
         let (K, c) = mlkem_encaps::<KEM_512, _>(&ek_pke.ek, &mut rng).unwrap();
 
         let dec = mlkem_decaps::<KEM_512>(&c, &dk_pke.dk).unwrap();
         // Assert that the decrypted message matches the original message
-        assert_eq!(dec, K);
+        assert!(bool::from(dec.ct_eq(&K)));
     }
 
     #[test]
     #[allow(non_snake_case)]
     fn roundtrip_1024() {
         let mut rng = thread_rng();
-        let (ek_pke, dk_pke) = ml_kem_keygen::<KEM_1024, _>(&mut rng); // Generate key pair
+        let (ek_pke, dk_pke) = ml_kem_keygen::<KEM_1024, _>(&mut rng).unwrap(); // Generate key pair
 
         let (K, c) = mlkem_encaps::<KEM_1024, _>(&ek_pke.ek, &mut rng).unwrap();
 
         let dec = mlkem_decaps::<KEM_1024>(&c, &dk_pke.dk).unwrap();
         // Assert that the decrypted message matches the original message
-        assert_eq!(dec, K);
+        assert!(bool::from(dec.ct_eq(&K)));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn roundtrip_from_ek_struct() {
+        let mut rng = thread_rng();
+        let (ek, dk) = ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap();
+
+        let (K, c) = mlkem_encaps_from_ek(&ek, &mut rng).unwrap();
+
+        let dec = mlkem_decaps::<KEM_768>(&c, &dk.dk).unwrap();
+        assert!(bool::from(dec.ct_eq(&K)));
     }
 
     #[test]
     #[allow(non_snake_case)]
     fn test_private_key_redaction() {
         let mut rng = thread_rng();
-        let (_, dk) = ml_kem_keygen::<KEM_768, _>(&mut rng);
+        let (_, dk) = ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap();
 
         // Verify that Debug output doesn't leak secret material
         let debug_output = format!("{:?}", dk);
         assert!(debug_output.contains("<redacted>"));
-        assert!(!debug_output.contains(&format!("{:?}", dk.dk)));
+        assert!(!debug_output.contains(&format!("{:?}", &dk.dk[..])));
 
         // Verify that Display output doesn't leak secret material
         let display_output = format!("{}", dk);
         assert!(display_output.contains("<redacted>"));
     }
+
+    #[test]
+    fn parameter_set_labels() {
+        assert_eq!(KEM_512::LABEL, "ML-KEM-512");
+        assert_eq!(KEM_768::LABEL, "ML-KEM-768");
+        assert_eq!(KEM_1024::LABEL, "ML-KEM-1024");
+    }
+
+    #[test]
+    fn security_categories() {
+        assert_eq!(KEM_512::NIST_SECURITY_CATEGORY, 1);
+        assert_eq!(KEM_768::NIST_SECURITY_CATEGORY, 3);
+        assert_eq!(KEM_1024::NIST_SECURITY_CATEGORY, 5);
+
+        assert!(!KEM_512::meets_security_category(3));
+        assert!(KEM_768::meets_security_category(3));
+        assert!(KEM_1024::meets_security_category(3));
+    }
+
+    #[test]
+    fn ciphertext_length_matches_bytes_per_poly() {
+        fn check<P: ParameterSet>(k: usize) {
+            let mut rng = thread_rng();
+            let (pk, _) = ml_kem_keygen::<P, _>(&mut rng).unwrap();
+            assert_eq!(pk.ek.len(), k * P::BYTES_PER_T_POLY + 32);
+            let (_, c) = mlkem_encaps::<P, _>(&pk.ek, &mut rng).unwrap();
+            assert_eq!(c.len(), k * P::BYTES_PER_U_POLY + P::BYTES_PER_V_POLY);
+        }
+        check::<KEM_512>(2);
+        check::<KEM_768>(3);
+        check::<KEM_1024>(4);
+    }
+
+    #[test]
+    fn test_ek_hash_matches() {
+        let mut rng = thread_rng();
+        let (_, mut dk) = ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap();
+        assert!(dk.ek_hash_matches());
+
+        // flip a bit inside the embedded ek
+        let mut bytes = dk.dk.to_vec();
+        let idx = bytes.len() - 100;
+        bytes[idx] ^= 1;
+        dk.dk = bytes.try_into().unwrap();
+        assert!(!dk.ek_hash_matches());
+    }
+
+    #[test]
+    fn public_key_from_str() {
+        use capy_kem::{error::KemError, fips203::keygen::KEMPublicKey};
+
+        fn to_hex(b: &[u8]) -> String {
+            b.iter().map(|x| format!("{x:02x}")).collect()
+        }
+
+        fn to_base64(b: &[u8]) -> String {
+            const ALPHABET: &[u8; 64] =
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+            let mut out = String::new();
+            for chunk in b.chunks(3) {
+                let mut buf = [0u8; 3];
+                buf[..chunk.len()].copy_from_slice(chunk);
+                let x = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]);
+                for i in 0..4 {
+                    if i <= chunk.len() {
+                        out.push(ALPHABET[(x >> (18 - 6 * i) & 0x3F) as usize] as char);
+                    } else {
+                        out.push('=');
+                    }
+                }
+            }
+            out
+        }
+
+        let mut rng = thread_rng();
+        let (pk, _) = ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap();
+
+        let hex = to_hex(&pk.ek);
+        let parsed = KEMPublicKey::<KEM_768>::try_from(hex.as_str()).unwrap();
+        assert_eq!(parsed.ek, pk.ek);
+
+        let b64 = to_base64(&pk.ek);
+        let parsed = KEMPublicKey::<KEM_768>::try_from(b64.as_str()).unwrap();
+        assert_eq!(parsed.ek, pk.ek);
+
+        let body: Vec<String> = b64
+            .as_bytes()
+            .chunks(64)
+            .map(|l| String::from_utf8(l.to_vec()).unwrap())
+            .collect();
+        let pem = format!(
+            "-----BEGIN ML-KEM-768 PUBLIC KEY-----\n{}\n-----END ML-KEM-768 PUBLIC KEY-----\n",
+            body.join("\n")
+        );
+        let parsed = KEMPublicKey::<KEM_768>::try_from(pem.as_str()).unwrap();
+        assert_eq!(parsed.ek, pk.ek);
+
+        // right encoding, wrong parameter set
+        assert_eq!(
+            KEMPublicKey::<KEM_512>::try_from(hex.as_str()).unwrap_err(),
+            KemError::InvalidInput
+        );
+        assert!(KEMPublicKey::<KEM_768>::try_from("not a key").is_err());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn validated_public_key_roundtrip() {
+        use capy_kem::fips203::encrypt::ValidatedPublicKey;
+
+        let mut rng = thread_rng();
+        let (pk, sk) = ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap();
+        let validated = ValidatedPublicKey::new(pk).unwrap();
+
+        for _ in 0..4 {
+            let (K, c) = validated.encaps(&mut rng).unwrap();
+            assert!(bool::from(
+                mlkem_decaps::<KEM_768>(&c, &sk.dk).unwrap().ct_eq(&K)
+            ));
+        }
+    }
+
+    #[test]
+    fn validated_public_key_rejects_bad_key() {
+        use capy_kem::fips203::{encrypt::ValidatedPublicKey, keygen::KEMPublicKey};
+
+        // all-ones coefficients are >= q and fail the modulus check
+        let hex = "ff".repeat(384 * 3 + 32);
+        let pk = KEMPublicKey::<KEM_768>::try_from(hex.as_str()).unwrap();
+        assert!(ValidatedPublicKey::new(pk).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn decaps_explicit_reports_rejection() {
+        use capy_kem::fips203::decrypt::mlkem_decaps_explicit;
+
+        let mut rng = thread_rng();
+        let (pk, sk) = ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap();
+        let (k, mut c) = mlkem_encaps::<KEM_768, _>(&pk.ek, &mut rng).unwrap();
+
+        let (ss, valid) = mlkem_decaps_explicit::<KEM_768>(&c, &sk.dk).unwrap();
+        assert!(valid);
+        assert!(bool::from(ss.ct_eq(&k)));
+
+        c[0] ^= 1;
+        let (ss, valid) = mlkem_decaps_explicit::<KEM_768>(&c, &sk.dk).unwrap();
+        assert!(!valid);
+        let implicit = mlkem_decaps::<KEM_768>(&c, &sk.dk).unwrap();
+        assert!(bool::from(ss.ct_eq(&implicit)));
+    }
+
+    #[test]
+    fn mutual_key_agreement() {
+        use capy_kem::fips203::key_agreement::key_agreement;
+
+        let mut rng = thread_rng();
+        let (alice_pk, alice_sk) = ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap();
+        let (bob_pk, bob_sk) = ml_kem_keygen::<KEM_768, _>(&mut rng).unwrap();
+
+        let (alice, c_alice) = key_agreement(&bob_pk, &mut rng).unwrap();
+        let (bob, c_bob) = key_agreement(&alice_pk, &mut rng).unwrap();
+
+        let k_alice = alice.finish(&alice_sk, &c_bob).unwrap();
+        let k_bob = bob.finish(&bob_sk, &c_alice).unwrap();
+        assert!(bool::from(k_alice.ct_eq(&k_bob)));
+    }
+
+    #[test]
+    fn size_functions_match_outputs() {
+        use capy_kem::fips203::keygen::keygen_from_prf_output;
+        use capy_kem::{ciphertext_size, private_key_size, public_key_size};
+        use rand::{rngs::StdRng, SeedableRng};
+
+        fn check<P: ParameterSet>() {
+            let (pk, sk) = keygen_from_prf_output::<P>(&[42u8; 64]);
+            let (_, c) = mlkem_encaps::<P, _>(&pk.ek, &mut StdRng::seed_from_u64(0)).unwrap();
+            assert_eq!(pk.ek.len(), public_key_size::<P>());
+            assert_eq!(sk.dk.len(), private_key_size::<P>());
+            assert_eq!(c.len(), ciphertext_size::<P>());
+        }
+        check::<KEM_512>();
+        check::<KEM_768>();
+        check::<KEM_1024>();
+
+        // usable in const context, e.g. for fixed-size frames
+        const _: [u8; ciphertext_size::<KEM_768>()] = [0; 1088];
+        assert_eq!(public_key_size::<KEM_768>(), 1184);
+        assert_eq!(private_key_size::<KEM_768>(), 2400);
+    }
+
+    #[test]
+    fn decaps_rejects_wrong_lengths() {
+        use capy_kem::{
+            fips203::{
+                decrypt::mlkem_decaps_into,
+                kem::{make_kem, AnyKem, KeyEncapsulationMechanism, MlKem},
+                keygen::keygen_from_prf_output,
+            },
+            KemError,
+        };
+        use rand::{rngs::StdRng, SeedableRng};
+
+        fn check<P: ParameterSet>(variant: AnyKem) {
+            let (pk, sk) = keygen_from_prf_output::<P>(&[7u8; 64]);
+            let (_, c) = mlkem_encaps::<P, _>(&pk.ek, &mut StdRng::seed_from_u64(0)).unwrap();
+            let dk = sk.dk.to_vec();
+            let long = |b: &[u8]| [b, &[0]].concat();
+
+            let bad_c = [Vec::new(), c[..c.len() - 1].to_vec(), long(&c)];
+            let bad_dk = [Vec::new(), dk[..dk.len() - 1].to_vec(), long(&dk)];
+            let inputs = bad_c
+                .iter()
+                .map(|c| (c.as_slice(), dk.as_slice()))
+                .chain(bad_dk.iter().map(|dk| (c.as_slice(), dk.as_slice())));
+
+            let dyn_kem = make_kem(variant);
+            for (c, dk) in inputs {
+                let err = Err(KemError::InvalidInput);
+                assert_eq!(mlkem_decaps::<P>(c, dk).map(|_| ()), err);
+                assert_eq!(mlkem_decaps_into::<P>(c, dk, &mut [0u8; 32]), err);
+                assert_eq!(dyn_kem.decaps(dk, c).map(|_| ()), err);
+                #[cfg(feature = "testing")]
+                assert_eq!(
+                    capy_kem::fips203::decrypt::mlkem_decaps_explicit::<P>(c, dk).map(|_| ()),
+                    err
+                );
+            }
+            // the typed key is length-checked already, only `c` can be off
+            for c in bad_c.iter() {
+                assert_eq!(
+                    MlKem::<P>::decaps(&sk, c).map(|_| ()),
+                    Err(KemError::InvalidInput)
+                );
+            }
+        }
+        check::<KEM_512>(AnyKem::MlKem512);
+        check::<KEM_768>(AnyKem::MlKem768);
+        check::<KEM_1024>(AnyKem::MlKem1024);
+    }
+
+    #[test]
+    fn zero_rng_is_rejected() {
+        use capy_kem::KemError;
+        use rand::{CryptoRng, RngCore};
+
+        struct ZeroRng;
+
+        impl RngCore for ZeroRng {
+            fn next_u32(&mut self) -> u32 {
+                0
+            }
+            fn next_u64(&mut self) -> u64 {
+                0
+            }
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                dest.fill(0);
+            }
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+                dest.fill(0);
+                Ok(())
+            }
+        }
+
+        impl CryptoRng for ZeroRng {}
+
+        assert_eq!(
+            ml_kem_keygen::<KEM_768, _>(&mut ZeroRng).unwrap_err(),
+            KemError::InsufficientEntropy
+        );
+
+        let (pk, _) = ml_kem_keygen::<KEM_768, _>(&mut thread_rng()).unwrap();
+        assert_eq!(
+            mlkem_encaps::<KEM_768, _>(&pk.ek, &mut ZeroRng).unwrap_err(),
+            KemError::InsufficientEntropy
+        );
+    }
+
+    #[test]
+    fn kem_trait_roundtrip() {
+        use capy_kem::fips203::kem::{KeyEncapsulationMechanism, MlKem};
+
+        fn roundtrip<K: KeyEncapsulationMechanism>()
+        where
+            K::SharedSecret: ConstantTimeEq,
+        {
+            let mut rng = thread_rng();
+            let (pk, sk) = K::keygen(&mut rng).unwrap();
+            let (k, c) = K::encaps(&pk, &mut rng).unwrap();
+            assert!(bool::from(K::decaps(&sk, &c).unwrap().ct_eq(&k)));
+        }
+        roundtrip::<MlKem<KEM_512>>();
+        roundtrip::<MlKem<KEM_768>>();
+        roundtrip::<MlKem<KEM_1024>>();
+    }
+
+    #[test]
+    fn dyn_kem_roundtrip() {
+        use capy_kem::{
+            fips203::kem::{make_kem, AnyKem},
+            KemError,
+        };
+
+        let mut rng = thread_rng();
+        for variant in [AnyKem::MlKem512, AnyKem::MlKem768, AnyKem::MlKem1024] {
+            let kem = make_kem(variant);
+            let (ek, dk) = kem.keygen(&mut rng).unwrap();
+            let (k, c) = kem.encaps(&ek, &mut rng).unwrap();
+            assert!(
+                bool::from(kem.decaps(&dk, &c).unwrap().ct_eq(&k)),
+                "{}",
+                kem.label()
+            );
+            assert_eq!(
+                kem.decaps(&dk, &c[1..]).unwrap_err(),
+                KemError::InvalidInput
+            );
+        }
+    }
+
+    // Long-running: `cargo test -- --ignored stress`
+    fn keygen_stress<P: ParameterSet>() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        const SEED: u64 = 0;
+        let mut rng = ChaCha20Rng::seed_from_u64(SEED);
+        for i in 0..10_000 {
+            let (pk, sk) = ml_kem_keygen::<P, _>(&mut rng).unwrap();
+            let (k, c) = mlkem_encaps::<P, _>(&pk.ek, &mut rng).unwrap();
+            let dec = mlkem_decaps::<P>(&c, &sk.dk).unwrap();
+            assert!(
+                bool::from(dec.ct_eq(&k)),
+                "{}: mismatch at iteration {i}, seed {SEED}",
+                P::LABEL
+            );
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_keygen_stress_512() {
+        keygen_stress::<KEM_512>();
+    }
+
+    #[test]
+    #[ignore]
+    fn test_keygen_stress_768() {
+        keygen_stress::<KEM_768>();
+    }
+
+    #[test]
+    #[ignore]
+    fn test_keygen_stress_1024() {
+        keygen_stress::<KEM_1024>();
+    }
 }